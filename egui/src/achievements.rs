@@ -0,0 +1,69 @@
+use egui::{Align2, Area, Context, Id, Order, RichText, Ui};
+use ifengine::core::Unlocked;
+
+use crate::{app_impl::TEXT_SMALL, theme::global_theme};
+
+/// Seconds for which an unlock toast is shown
+pub static TOAST_DURATION: f64 = 4.0;
+
+/// A toast for each newly unlocked achievement.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub title: String,
+    pub since: f64,
+}
+
+/// Draw the unlock toasts in the bottom-right corner, dropping expired ones.
+pub fn show_toasts(ctx: &Context, toasts: &mut Vec<Toast>) {
+    let now = ctx.input(|i| i.time);
+    toasts.retain(|t| now - t.since < TOAST_DURATION);
+    if toasts.is_empty() {
+        return;
+    }
+
+    Area::new(Id::new("achievement_toasts"))
+        .order(Order::Foreground)
+        .anchor(Align2::RIGHT_BOTTOM, [-20.0, -40.0])
+        .show(ctx, |ui| {
+            for toast in toasts.iter() {
+                egui::Frame {
+                    fill: global_theme().get_color("bg_secondary").unwrap_or_default(),
+                    inner_margin: egui::Margin::symmetric(12, 8),
+                    ..Default::default()
+                }
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Achievement unlocked").size(TEXT_SMALL));
+                    ui.label(&toast.title);
+                });
+                ui.add_space(6.0);
+            }
+        });
+
+    ctx.request_repaint_after(std::time::Duration::from_secs_f64(0.5));
+}
+
+/// List the achievements of the story, hiding the description of locked achievements marked as hidden.
+pub fn render_achievements(ui: &mut Ui, achievements: &story::Achievements, unlocked: &Unlocked) {
+    ui.heading("Achievements");
+    ui.label(format!("{} / {}", unlocked.len(), achievements.list.len()));
+    ui.add_space(10.0);
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for achievement in achievements.iter() {
+            let done = unlocked.contains(&achievement.id);
+            let (title, description) = if achievement.hidden && !done {
+                ("???", "Hidden")
+            } else {
+                (achievement.title.as_str(), achievement.description.as_str())
+            };
+
+            ui.add_enabled_ui(done, |ui| {
+                ui.label(RichText::new(title).strong());
+                if !description.is_empty() {
+                    ui.label(RichText::new(description).size(TEXT_SMALL));
+                }
+            });
+            ui.add_space(8.0);
+        }
+    });
+}
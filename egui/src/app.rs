@@ -1,12 +1,17 @@
 use egui_snarl::Snarl;
-pub use story::{Game, new};
+use ifengine::core::Unlocked;
+pub use story::{Achievements, Game, achievements, new};
 
-use crate::graph::Node;
+use crate::{achievements::Toast, graph::Node};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct App {
     pub game: Game,
     pub state: GUIState,
+    #[cfg_attr(feature = "serde", serde(skip, default = "achievements"))]
+    pub achievements: Achievements,
+    /// Persisted across playthroughs
+    pub unlocked: Unlocked,
 }
 
 impl App {
@@ -14,6 +19,8 @@ impl App {
         Self {
             game: new(),
             state: GUIState::new(),
+            achievements: achievements(),
+            unlocked: Unlocked::new(),
         }
     }
 
@@ -37,6 +44,7 @@ pub struct GUIState {
     pub show_menu_button: bool,
     pub show_menu: bool,
     pub show_graph: bool,
+    pub show_achievements: bool,
 
     pub transitioning: bool,
 
@@ -44,6 +52,11 @@ pub struct GUIState {
 
     #[cfg_attr(feature = "serde", serde(skip))]
     pub last_view: Option<ifengine::View>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub toasts: Vec<Toast>,
+    /// Time of the last [`ifengine::Game::tick`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub last_tick: Option<f64>,
     pub fade_duration: [f32; 2],
 }
 
@@ -1,11 +1,16 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
 use egui::{FontFamily, FontId, Margin, RichText, TextStyle, style::ScrollStyle};
 use egui_alignments::center_vertical;
 use egui_snarl::ui::SnarlWidget;
+use ifengine::GameError;
 
 use crate::{
     App,
+    achievements::{Toast, render_achievements, show_toasts},
     graph::GraphViewer,
     render::render,
     theme::{global_theme, global_theme_mut},
@@ -33,16 +38,20 @@ impl eframe::App for App {
                 ctx,
                 |ui| {
                     let (snarl, viewer) = self.graph_viewer.get_or_insert_with(|| {
-                        let (snarl, prefix_len) =
+                        let (snarl, prefix_len, truncated) =
                             crate::graph::new_snarl(ui.available_width(), ui.available_height());
                         (
                             snarl,
                             GraphViewer {
                                 prefix_len,
+                                truncated,
                                 init_transform: None,
                             },
                         )
                     });
+                    if let Some(truncated) = &viewer.truncated {
+                        ui.weak(truncated);
+                    }
                     SnarlWidget::new()
                         .id(egui::Id::new("snarl-demo"))
                         .style(global_theme().snarl)
@@ -54,6 +63,18 @@ impl eframe::App for App {
             );
         }
         self.show_graph = show_graph;
+
+        let mut show_achievements = self.show_achievements;
+        if show_achievements {
+            show_overlay(
+                ctx,
+                |ui| render_achievements(ui, &self.achievements, &self.unlocked),
+                || {
+                    show_achievements = false;
+                },
+            );
+        }
+        self.show_achievements = show_achievements;
         egui::TopBottomPanel::top("top_panel")
             .frame(egui::Frame {
                 inner_margin: egui::Margin::symmetric(5, 12),
@@ -78,6 +99,30 @@ impl eframe::App for App {
                             let style = ui.style_mut();
                             style.spacing.item_spacing = MENU_SPACING;
 
+                            if ui
+                                .add_enabled(
+                                    self.game.history().can_undo(),
+                                    egui::Button::new("Undo"),
+                                )
+                                .clicked()
+                            {
+                                self.game.undo();
+                            }
+
+                            if ui
+                                .add_enabled(
+                                    self.game.history().can_redo(),
+                                    egui::Button::new("Redo"),
+                                )
+                                .clicked()
+                            {
+                                self.game.redo();
+                            }
+
+                            if ui.button("Achievements").clicked() {
+                                self.show_achievements = true;
+                            }
+
                             if ui.button("Graph").clicked() {
                                 self.show_graph = true;
                             }
@@ -97,13 +142,34 @@ impl eframe::App for App {
                 });
             });
 
+        let now = ctx.input(|i| i.time);
+        if let Some(last) = self.state.last_tick.replace(now)
+            && let Err(e) = self.game.tick(Duration::from_secs_f64(now - last))
+        {
+            self.show_error(ctx, &e);
+            show_toasts(ctx, &mut self.state.toasts);
+            return;
+        }
+
         let resp = match self.game.view() {
             Ok(view) => view,
             Err(e) => {
-                dbg!(e);
-                todo!()
+                self.show_error(ctx, &e);
+                show_toasts(ctx, &mut self.state.toasts);
+                return;
             }
         };
+
+        if let Some(remaining) = self.game.next_timer() {
+            ctx.request_repaint_after(remaining.min(Duration::from_secs(1)));
+        }
+
+        for achievement in self.achievements.check(&self.game, &mut self.unlocked) {
+            self.state.toasts.push(Toast {
+                title: achievement.title.clone(),
+                since: now,
+            });
+        }
         egui::CentralPanel::default()
             .frame(egui::Frame {
                 fill: ctx.style().visuals.window_fill, // body
@@ -199,6 +265,42 @@ impl eframe::App for App {
                     )
                 });
             });
+
+        show_toasts(ctx, &mut self.state.toasts);
+    }
+}
+
+impl App {
+    /// Show a game error in place of the view, offering to undo or restart.
+    fn show_error(&mut self, ctx: &egui::Context, e: &GameError) {
+        egui::CentralPanel::default()
+            .frame(egui::Frame {
+                fill: ctx.style().visuals.window_fill, // body
+                ..Default::default()
+            })
+            .show(ctx, |ui| {
+                center_vertical(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.label(e.to_string());
+                        ui.add_space(20.0);
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(
+                                    self.game.history().can_undo(),
+                                    egui::Button::new("Undo"),
+                                )
+                                .clicked()
+                            {
+                                self.game.undo();
+                            }
+                            if ui.button("Restart").clicked() {
+                                self.game = crate::new();
+                                self.state.last_view = None;
+                            }
+                        });
+                    });
+                });
+            });
     }
 }
 
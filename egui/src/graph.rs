@@ -6,7 +6,7 @@ use egui_snarl::{
     InPin, InPinId, NodeId, OutPin, OutPinId, Snarl,
     ui::{SnarlPin, SnarlViewer},
 };
-use ifengine::run::PageRecord;
+use ifengine::run::{PageRecord, SimulationConfig, SimulationStatus};
 use rand::Rng;
 
 #[derive(Debug, Clone)]
@@ -19,9 +19,19 @@ pub struct Node {
 }
 
 // todo: layout, connecting to same pin, disable body showing for empty body
-pub fn new_snarl(width: f32, height: f32) -> (Snarl<Node>, usize) {
+pub fn new_snarl(width: f32, height: f32) -> (Snarl<Node>, usize, Option<String>) {
     let app = new();
-    let sim = app.simulate(|s| s.depth <= 20);
+    let config = SimulationConfig::new()
+        .with_max_depth(20)
+        .with_max_states(20_000);
+    #[cfg(not(target_arch = "wasm32"))]
+    let sim = app.simulate_parallel(&config, |_| true);
+    #[cfg(target_arch = "wasm32")]
+    let sim = app.simulate_with(&config, |_| true);
+    let truncated = match &sim.status {
+        SimulationStatus::Complete => None,
+        SimulationStatus::Truncated(reason) => Some(format!("Truncated: {reason}")),
+    };
 
     // node_id -> snarl_node_id, output_count
     let mut seen = HashMap::new();
@@ -113,7 +123,7 @@ pub fn new_snarl(width: f32, height: f32) -> (Snarl<Node>, usize) {
             .unwrap_or_default()
     }
 
-    (snarl, lcp.map(|s| s.len()).unwrap_or(0))
+    (snarl, lcp.map(|s| s.len()).unwrap_or(0), truncated)
 }
 
 pub const SCALING: f32 = 1.5; // snarl elements scale
@@ -136,6 +146,9 @@ fn random_pos(p_x: f32, _p_y: f32, width: f32, height: f32, margin: egui::Margin
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GraphViewer {
     pub prefix_len: usize,
+    /// Why the simulation was truncated, if it was
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub truncated: Option<String>,
     pub init_transform: Option<egui::emath::TSTransform>,
 }
 
@@ -197,11 +210,28 @@ impl SnarlViewer<Node> for GraphViewer {
         };
         ui.vertical(|ui| {
             if !record.tags.is_empty() {
-                ui.label(format_list("Tags: ", record.tags.iter()));
+                ui.label(format_list(
+                    "Tags: ",
+                    record
+                        .tags
+                        .iter()
+                        .map(|(tag, kind)| format!("{tag} ({kind})")),
+                ));
             };
             if !record.ends.is_empty() {
                 ui.label(format_list("Ends: ", record.ends.iter()));
             };
+            if !record.end_vars.is_empty() {
+                ui.label(format_list("Vars: ", record.end_vars.values().flatten()));
+            };
+            if record.never_selectable().next().is_some() {
+                ui.label(format_list(
+                    "Never selectable: ",
+                    record
+                        .never_selectable()
+                        .map(|(choice, reason)| format!("{choice} ({reason})")),
+                ));
+            };
         });
     }
 
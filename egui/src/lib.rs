@@ -9,4 +9,5 @@ pub use app::*;
 mod app_impl;
 pub use app_impl::*;
 
+pub mod achievements;
 pub mod graph;
@@ -1,22 +1,23 @@
 use egui::Ui;
 
 use ifengine::{
-    View,
-    core::GameInner,
-    view::{Image, ImageVariant, Object},
+    Action, View,
+    core::game_state::InternalKey,
+    view::{Image, ImageVariant, Input, Object},
 };
 
 use crate::{
+    app::Game,
     utils::UiExt,
     view::{ElementExt, LineExt},
 };
 
 // i don't think theres a nice way to extract this to ifengine crate, so this logic ig is fine to require each project to reimplement
 
-pub fn render(view: View, ui: &mut Ui, mut game: Option<&mut GameInner>) {
-    let name = view.name();
+pub fn render(view: View, ui: &mut Ui, mut game: Option<&mut Game>) {
+    let owners: Vec<_> = (0..view.len()).map(|i| view.owner(i).clone()).collect();
     let mut first = true;
-    for object in view {
+    for (object, owner) in view.into_iter().zip(owners) {
         match object {
             Object::Paragraph(line) => {
                 if !first {
@@ -35,10 +36,14 @@ pub fn render(view: View, ui: &mut Ui, mut game: Option<&mut GameInner>) {
                     ui.draw_empty(1);
                     ui.spacing_mut().item_spacing = egui::vec2(0.0, 10.0);
 
-                    for (i, line) in choices.into_iter() {
-                        if line.ui_clicked(ui, game.as_deref_mut()) {
+                    for (i, line, reason) in choices.into_iter() {
+                        if let Some(reason) = reason {
+                            ui.add_enabled_ui(false, |ui| line.ui(ui, None))
+                                .inner
+                                .on_disabled_hover_text(reason);
+                        } else if line.ui_clicked(ui, game.as_deref_mut()) {
                             if let Some(game) = game.as_mut() {
-                                game.handle_choice((name.clone(), key.clone()), i);
+                                game.handle_choice((owner.clone(), key), i);
                             }
                         }
                     }
@@ -46,6 +51,19 @@ pub fn render(view: View, ui: &mut Ui, mut game: Option<&mut GameInner>) {
                 });
             }
             Object::Image(img) => render_image(img, ui, game.as_deref_mut()),
+            Object::Input(key, input) => {
+                let key = (owner.clone(), key);
+                let stored = game
+                    .as_mut()
+                    .and_then(|game| game.state.get_page(owner.clone()).get_text(&key.1).cloned());
+                ui.draw_empty(1);
+                if let Some(value) = render_input(&key, input, stored, ui)
+                    && let Some(game) = game.as_mut()
+                {
+                    let _ = game.handle_action(Action::SetText(key, value));
+                }
+                ui.draw_empty(1);
+            }
             Object::Heading(line, level) => {
                 line.add_as_heading(ui, level);
             }
@@ -61,6 +79,15 @@ pub fn render(view: View, ui: &mut Ui, mut game: Option<&mut GameInner>) {
             Object::Note(_, _) => {
                 todo!()
             }
+            Object::Timer(timer) => {
+                // timers without an action only wake the page
+                if !matches!(timer.action, Action::None)
+                    && let Some(game) = game.as_deref()
+                {
+                    let remaining = timer.remaining(game.clock()).as_secs_f32().ceil();
+                    ui.label(egui::RichText::new(format!("{remaining:.0}s")).small());
+                }
+            }
             Object::Custom(_) => {
                 unimplemented!()
             }
@@ -69,7 +96,37 @@ pub fn render(view: View, ui: &mut Ui, mut game: Option<&mut GameInner>) {
     }
 }
 
-fn render_image(img: Image, ui: &mut Ui, mut game: Option<&mut GameInner>) {
+/// Returns the value once submitted with enter
+/// The field starts out with the value stored in the game, if any.
+fn render_input(
+    key: &InternalKey,
+    input: Input,
+    stored: Option<String>,
+    ui: &mut Ui,
+) -> Option<String> {
+    // the buffer lives in egui memory until submitted
+    let id = ui.make_persistent_id(key);
+    let mut buffer = ui
+        .ctx()
+        .data_mut(|d| d.get_temp::<String>(id))
+        .or(stored)
+        .unwrap_or_default();
+
+    let resp = ui.add(egui::TextEdit::singleline(&mut buffer).hint_text(input.placeholder));
+    let submitted = resp.lost_focus()
+        && ui.input(|i| i.key_pressed(egui::Key::Enter))
+        && !buffer.trim().is_empty();
+
+    if submitted {
+        ui.ctx().data_mut(|d| d.remove::<String>(id));
+        Some(buffer.trim().to_string())
+    } else {
+        ui.ctx().data_mut(|d| d.insert_temp(id, buffer));
+        None
+    }
+}
+
+fn render_image(img: Image, ui: &mut Ui, mut game: Option<&mut Game>) {
     let Image {
         size: [w, h],
         variant,
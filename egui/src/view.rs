@@ -1,11 +1,8 @@
-use crate::{theme::global_theme, utils::UiExt};
+use crate::{app::Game, theme::global_theme, utils::UiExt};
 
 use easy_ext::ext;
 use egui::{Color32, FontFamily, Response, RichText, Ui};
-use ifengine::{
-    core::GameInner,
-    view::{Line, Modifier, Span, SpanVariant},
-};
+use ifengine::view::{Line, Modifier, Span, SpanVariant};
 
 #[ext(ElementExt)]
 impl Span {
@@ -202,7 +199,7 @@ impl RichText {
 // todo: configurable effect on sensed hover
 #[ext(LineExt)]
 impl Line {
-    pub fn ui(self, ui: &mut Ui, mut game: Option<&mut GameInner>) -> Response {
+    pub fn ui(self, ui: &mut Ui, mut game: Option<&mut Game>) -> Response {
         ui.scope(|ui| {
             ui.spacing_mut().item_spacing = egui::vec2(0.0, 10.0);
             
@@ -223,7 +220,7 @@ impl Line {
         .response
     }
     
-    pub fn ui_clicked(self, ui: &mut Ui, mut game: Option<&mut GameInner>) -> bool {
+    pub fn ui_clicked(self, ui: &mut Ui, mut game: Option<&mut Game>) -> bool {
         let mut clicked = false;
         let ui_resp = ui.horizontal_wrapped(|ui| {
             for mut element in self.spans {
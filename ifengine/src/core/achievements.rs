@@ -0,0 +1,204 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::Game;
+use crate::core::{Condition, GameContext, PageId};
+use crate::run::SimulationState;
+
+/// A milestone which unlocks once its condition holds, see [`Achievements`].
+#[derive(Debug, Clone)]
+pub struct Achievement<C> {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// Hidden achievements should not be listed until they are unlocked.
+    pub hidden: bool,
+    pub condition: Condition<C>,
+}
+
+impl<C: GameContext> Achievement<C> {
+    pub fn new(id: impl Into<String>, title: impl Into<String>, condition: Condition<C>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            description: String::new(),
+            hidden: false,
+            condition,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    pub fn holds(&self, game: &Game<C>) -> bool {
+        (self.condition)(&game.context, &game.tags)
+    }
+}
+
+/// The achievements of a story.
+///
+/// Conditions are checked against the [context](Game::context) and [tags](crate::core::GameTags) of a game, and unlocks are recorded into an [`Unlocked`], which outlives any single playthrough.
+///
+/// # Example
+/// ```rust,ignore
+/// pub fn achievements() -> Achievements<State> {
+///     Achievements::new()
+///         .with(Achievement::new("crew", "Full crew", |s, _| s.crew.len() == 3))
+///         .with(
+///             Achievement::new("oracle", "Second sight", |_, tags| tags.contains("oracle"))
+///                 .with_hidden(true),
+///         )
+/// }
+///
+/// // after each view
+/// for a in achievements.check(&game, &mut unlocked) {
+///     toast(&a.title);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Achievements<C> {
+    pub list: Vec<Achievement<C>>,
+}
+
+impl<C: GameContext> Achievements<C> {
+    pub fn new() -> Self {
+        Self { list: Vec::new() }
+    }
+
+    pub fn with(mut self, achievement: Achievement<C>) -> Self {
+        self.list.push(achievement);
+        self
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Achievement<C>> {
+        self.list.iter().find(|a| a.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Achievement<C>> {
+        self.list.iter()
+    }
+
+    /// Unlock every achievement whose condition now holds, returning the newly unlocked ones.
+    pub fn check(&self, game: &Game<C>, unlocked: &mut Unlocked) -> Vec<&Achievement<C>> {
+        let new: Vec<_> = self
+            .list
+            .iter()
+            .filter(|a| !unlocked.contains(&a.id) && a.holds(game))
+            .collect();
+
+        unlocked.0.extend(new.iter().map(|a| a.id.clone()));
+        new
+    }
+
+    /// Record the achievements which hold in a simulation state. Meant to be called from the visitor of [`Game::simulate`].
+    ///
+    /// Each achievement is only recorded once per path, see [`SimulationState::achieved`]. The simulated game itself is left untouched.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut report = AchievementReport::default();
+    /// let sim = game.simulate(|s| {
+    ///     achievements.observe(s, &mut report);
+    ///     s.depth <= 20
+    /// });
+    /// let missing = report.unreachable(&achievements);
+    /// ```
+    pub fn observe(&self, s: &mut SimulationState<C>, report: &mut AchievementReport) {
+        for achievement in &self.list {
+            if s.achieved.contains(&achievement.id) || !achievement.holds(s) {
+                continue;
+            }
+            s.achieved.insert(achievement.id.clone());
+
+            let reach = report.reachable.entry(achievement.id.clone()).or_default();
+            if let Some(last) = &s.last {
+                reach.pages.insert(last.clone());
+            }
+            reach.min_depth = reach.min_depth.min(s.depth);
+        }
+    }
+}
+
+/// The ids of unlocked achievements.
+///
+/// This is kept apart from [`Game`], so that starting over or restoring a save doesn't lose them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Unlocked(BTreeSet<String>);
+
+impl Unlocked {
+    pub fn new() -> Self {
+        Self(BTreeSet::new())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.0.contains(id)
+    }
+}
+
+/// The achievements reached during a simulation, see [`Achievements::observe`].
+#[derive(Debug, Default, Clone)]
+pub struct AchievementReport {
+    pub reachable: HashMap<String, Reach>,
+}
+
+/// How an achievement was reached during a simulation.
+#[derive(Debug, Clone)]
+pub struct Reach {
+    /// The pages whose interactions unlocked the achievement.
+    pub pages: HashSet<PageId>,
+    pub min_depth: usize,
+}
+
+impl Reach {
+    pub fn new() -> Self {
+        Self {
+            pages: HashSet::new(),
+            min_depth: usize::MAX,
+        }
+    }
+}
+
+impl AchievementReport {
+    pub fn is_reachable(&self, id: &str) -> bool {
+        self.reachable.contains_key(id)
+    }
+
+    /// Achievements which were never unlocked during the simulation.
+    pub fn unreachable<'a, C: GameContext>(
+        &self,
+        achievements: &'a Achievements<C>,
+    ) -> Vec<&'a Achievement<C>> {
+        achievements
+            .iter()
+            .filter(|a| !self.is_reachable(&a.id))
+            .collect()
+    }
+}
+
+// ----------------------- BOILERPLATE ---------------------------------------------------
+
+impl<C: GameContext> Default for Achievements<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Reach {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Deref for Unlocked {
+    type Target = BTreeSet<String>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
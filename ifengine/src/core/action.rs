@@ -1,18 +1,29 @@
-use crate::core::{PageHandle, game_state::InternalKey};
+use crate::core::{PageHandle, Var, game_state::InternalKey};
 
 /// Adds an effect to a [`crate::view::Span`]
 /// Spans with an action occlude their containing object (i.e. [`crate::view::Object::Choice`])
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub enum Action {
     #[default]
     None,
-    SetBit(InternalKey, u8),
+    SetBit(InternalKey, u16),
+    /// Resolve a timed choice to its default index, see [`crate::view::Timer`]
+    Timeout(InternalKey, u16),
     Set(InternalKey, u64),
     Inc(InternalKey),
+    /// Remove the value, selection and text at a key
     Reset(InternalKey),
+    /// Submit a text [input](crate::view::Object::Input)
+    SetText(InternalKey, String),
     /// The name of the handle here is just for debug, and NOT guaranteed to be the actual id of the page, see [`crate::core::PageState`]
     Next(PageHandle), // Arc for easy cloning
     Back(usize),
     Tunnel(PageHandle),
     Exit,
+    /// Set a game-wide [variable](crate::core::Vars)
+    SetVar(String, Var),
+    /// Add to an integer [variable](crate::core::Vars::inc)
+    IncVar(String, i64),
+    /// Flip a boolean [variable](crate::core::Vars::toggle)
+    ToggleVar(String),
 }
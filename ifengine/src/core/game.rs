@@ -1,8 +1,11 @@
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use crate::core::game_state::{GameState, InternalKey};
-use crate::core::{Page, PageHandle, PageId, Response};
-use crate::view::View;
+use crate::core::{History, Hooks, Page, PageHandle, PageId, Params, Response, TunnelValue, Vars};
+use crate::run::ContextHash;
+use crate::utils::{hash_str, mix_seed};
+use crate::view::{Object, Timer, View};
 use crate::{Action, GameError};
 
 /// Used to manage custom state
@@ -18,11 +21,43 @@ impl<T> GameContext for T where T: Default + Clone + std::fmt::Debug + 'static {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameInner {
     pub state: GameState,
+    /// Game-wide variables, see [`Vars`]
+    pub vars: Vars,
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) pages: PageStack,
-    fresh: bool,
-    last_id: PageId,
+    pub(crate) fresh: bool,
+    pub(crate) last_id: PageId,
     pub(crate) iterations: usize, // todo
+    /// Root of all randomness in the game, see [`Game::with_seed`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    seed: u64,
+    /// Whether an action or choice was handled since the last view, see [`History`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) interacted: bool,
+    /// The params of the page being called, see [`PageHandle::with_params`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) params: Option<Params>,
+    /// The value of the last [`Response::ExitWith`], and the page it returned to, until another page is viewed
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) tunnel_result: Option<(PageId, TunnelValue)>,
+    /// Time elapsed in game, advanced by [`Game::tick`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) clock: Duration,
+    /// The [timers](Timer) of the last view
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) timers: Vec<Timer>,
+    /// The storylets a [`crate::core::Deck`] could have switched to besides the one it drew, recorded while simulating
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) draws: Vec<PageHandle>,
+    /// The pages threads are being woven into, outermost first
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) weaving: Vec<PageId>,
+    /// Whether [`crate::core::Hook::on_enter_page`] has fired for the current page, which it hasn't yet in a restored game
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) entered: bool,
+    /// Whether [`crate::core::Hook::on_end`] has fired for the current ending
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) ended: bool,
 }
 
 /// Wraps [`GameInner`] with customizable a context used to represent the game-specific state.
@@ -56,6 +91,13 @@ pub struct Game<C = StringMap> {
     pub context: C,
     pub tags: GameTags,
     pub(crate) simulating: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) history: History<C>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) hooks: Hooks<C>,
+    /// See [`Game::with_context_hash`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) context_hash: Option<ContextHash<C>>,
 }
 
 impl<C: GameContext> Game<C> {
@@ -65,10 +107,21 @@ impl<C: GameContext> Game<C> {
 
         let inner = GameInner {
             state: GameState::new(),
+            vars: Vars::new(),
             pages: PageStack::new_with_page(widget),
             fresh: true, // doesn't matter
             last_id,
             iterations: 0,
+            interacted: false,
+            params: None,
+            tunnel_result: None,
+            clock: Duration::ZERO,
+            timers: Vec::new(),
+            draws: Vec::new(),
+            weaving: Vec::new(),
+            entered: false,
+            ended: false,
+            seed: Self::initial_seed(),
         };
 
         Self {
@@ -76,17 +129,68 @@ impl<C: GameContext> Game<C> {
             tags: Default::default(),
             inner,
             simulating: false,
+            history: Default::default(),
+            hooks: Default::default(),
+            context_hash: None,
         }
     }
 
+    /// Fix the seed used for randomness (i.e. [`crate::elements::alts`] and [`crate::core::Deck`]), making playthroughs reproducible.
+    ///
+    /// The seed is stored in [`GameInner`], so a restored save replays its random branches identically.
+    /// Without this, a random seed is picked when the `rand` feature is enabled, and 0 otherwise.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.inner.seed = seed;
+        self
+    }
+
+    #[cfg(feature = "rand")]
+    fn initial_seed() -> u64 {
+        rand::random()
+    }
+
+    #[cfg(not(feature = "rand"))]
+    fn initial_seed() -> u64 {
+        0
+    }
+
     pub fn simulating(&self) -> bool {
         self.simulating
     }
 
     /// Calls the active [page](PageHandle) in a loop, until a [`View`] is produced.
+    /// Invokes the page hooks if it resolves to another page (see [`crate::core::Hook`]), [`crate::core::Hook::on_view`], and [`crate::core::Hook::on_end`] once the game ends.
     pub fn view(&mut self) -> Result<View, GameError> {
+        match self.call_pages() {
+            Ok(mut view) => {
+                self.hook_view(&mut view);
+                self.timers = view
+                    .iter()
+                    .filter_map(|obj| match obj {
+                        Object::Timer(timer) => Some(timer.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                Ok(view)
+            }
+            Err(GameError::End) => {
+                // the ending is viewed every frame, but only reached once
+                if !self.ended {
+                    self.ended = true;
+                    self.hook_end();
+                }
+                Err(GameError::End)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn call_pages(&mut self) -> Result<View, GameError> {
         let Some(mut page) = self.pages.current() else {
-            return Err(GameError::NoPage);
+            return Err(GameError::NoPage {
+                page: self.last_id.clone(),
+                frames: self.pages.frames(),
+            });
         };
 
         if page.id.is_empty() {
@@ -94,17 +198,19 @@ impl<C: GameContext> Game<C> {
         }
 
         let view = loop {
-            let r = page.call(self);
+            let r = match page.call(self) {
+                Response::Weave(view, threads) => self.weave(view, threads),
+                r => r,
+            };
             match r {
                 Response::View(view) => {
                     page.id = view.pageid.clone(); // id the page by the fully resolved name
-                    self.fresh = self.last_id != page.id;
-                    if self.fresh {
-                        self.iterations += 1;
-                        self.last_id = page.id.clone()
-                    }
+                    self.viewed_tunnel_result(&page.id);
+                    let id = page.id.clone();
 
                     self.pages.push(page)?; // only rendered pages get added to history
+                    self.visit(&id);
+                    self.record_history();
 
                     break view;
                 }
@@ -112,44 +218,169 @@ impl<C: GameContext> Game<C> {
                     page = next;
                 }
                 Response::Back(n) => {
-                    page = self.pages.pop_n(n)?;
+                    page = self.pages.pop_n(n, &page.id)?;
                 }
                 Response::Tunnel(next) => {
                     self.pages.adv_stack();
+                    self.tunnel_result = None;
                     page = next;
                 }
                 Response::Exit => {
-                    self.pages.pop_stack().ok_or(GameError::End)?;
-                    page = self.pages.current().ok_or(GameError::NoPage)?;
+                    self.pages.exit_tunnel(&page.id)?;
+                    self.tunnel_result = None;
+                    page = self.pages.current().ok_or_else(|| GameError::NoPage {
+                        page: page.id.clone(),
+                        frames: self.pages.frames(),
+                    })?;
+                }
+                Response::ExitWith(value) => {
+                    self.pages.exit_tunnel(&page.id)?;
+                    page = self.pages.current().ok_or_else(|| GameError::NoPage {
+                        page: page.id.clone(),
+                        frames: self.pages.frames(),
+                    })?;
+                    self.tunnel_result = Some((page.id.clone(), value));
                 }
                 Response::End => return Err(GameError::End),
+                Response::Error(e) => return Err(e),
+                Response::Weave(..) => unreachable!(),
             }
         };
 
         Ok(view)
     }
 
-    pub fn id(&self) -> Option<PageId> {
-        let mut test = self.clone();
+    /// Called once a page has resolved its view: marks whether it is [fresh](GameInner::fresh),
+    /// and if so, invokes [`crate::core::Hook::on_leave_page`] for the last page and [`crate::core::Hook::on_enter_page`] for this one.
+    pub(crate) fn visit(&mut self, id: &PageId) {
+        self.fresh = self.last_id != *id;
+        let from = if self.fresh {
+            self.iterations += 1;
+            Some(std::mem::replace(&mut self.inner.last_id, id.clone()))
+        } else {
+            None
+        };
+        // a restored game enters the page it was saved on, without a page to leave
+        if self.fresh || !self.entered {
+            self.entered = true;
+            self.hook_pages(from.as_ref(), id);
+        }
+    }
+
+    /// Run each thread until it produces a view, and merge them into `view`.
+    /// Threads can only add to the page: any other response, or a page woven into itself, is an error.
+    ///
+    /// Threads are part of the visit of the page they are woven into, so their timers and once tags follow its [showing](GameInner::showing) state.
+    pub(crate) fn weave(&mut self, view: View, threads: Vec<(usize, PageHandle)>) -> Response {
+        if self.weaving.contains(&view.pageid) {
+            return Response::Error(GameError::WovenIntoItself(view.pageid));
+        }
+        self.weaving.push(view.pageid.clone());
+        let r = self.weave_threads(view, threads);
+        self.weaving.pop();
+        r
+    }
 
-        let Response::View(view) = self.pages.current()?.call(&mut test) else {
-            return None;
+    fn weave_threads(&mut self, mut view: View, threads: Vec<(usize, PageHandle)>) -> Response {
+        let mut offset = 0;
+        for (at, mut thread) in threads {
+            let thread_view = loop {
+                match thread.call(self) {
+                    Response::View(v) => break v,
+                    Response::Weave(v, t) => match self.weave(v, t) {
+                        Response::View(v) => break v,
+                        r => return r,
+                    },
+                    Response::Switch(next) => thread = next,
+                    Response::Error(e) => return Response::Error(e),
+                    _ => {
+                        return Response::Error(GameError::ThreadLeftPage {
+                            thread: thread.id,
+                            page: view.pageid,
+                        });
+                    }
+                }
+            };
+            let len = thread_view.len();
+            view.merge_thread(at + offset, thread_view);
+            offset += len;
+        }
+        Response::View(view)
+    }
+
+    /// Advance the [clock](GameInner::clock) by `elapsed`, firing the earliest [`Timer`] of the last view which has come due.
+    /// Frontends call this each frame; tests can pass any duration.
+    ///
+    /// Returns whether a timer fired, in which case the view should be refreshed.
+    pub fn tick(&mut self, elapsed: Duration) -> Result<bool, GameError> {
+        self.inner.clock += elapsed;
+
+        let Some(timer) = self
+            .inner
+            .timers
+            .iter()
+            .filter(|t| t.deadline <= self.inner.clock)
+            .min_by_key(|t| t.deadline)
+            .cloned()
+        else {
+            return Ok(false);
         };
 
-        Some(view.pageid)
+        // the next view registers its own timers
+        self.inner.timers.clear();
+        self.fire_timer(&timer)?;
+        Ok(true)
+    }
+
+    /// Advance the clock to the deadline of a timer, and [handle](Game::handle_action) its action.
+    pub fn fire_timer(&mut self, timer: &Timer) -> Result<(), GameError> {
+        self.inner.clock = self.inner.clock.max(timer.deadline);
+        match &timer.action {
+            Action::None => Ok(()),
+            action => self.handle_action(action.clone()),
+        }
+    }
+
+    /// A copy of the game without its [history](Game::history), for copies which are never undone, (i.e. those simulated or viewed ahead).
+    /// Unlike [`Clone`], this doesn't copy every snapshot of the history.
+    pub(crate) fn fork(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            context: self.context.clone(),
+            tags: self.tags.clone(),
+            simulating: self.simulating,
+            history: History::new(0),
+            hooks: self.hooks.clone(),
+            context_hash: self.context_hash,
+        }
+    }
+
+    pub fn id(&self) -> Option<PageId> {
+        let mut test = self.fork();
+
+        match self.pages.current()?.call(&mut test) {
+            Response::View(view) | Response::Weave(view, _) => Some(view.pageid),
+            _ => None,
+        }
     }
 }
 
 impl GameInner {
     // --------------- action handling -----------------------
-    pub fn handle_choice(&mut self, key: InternalKey, index: u8) {
+    /// Prefer [`Game::handle_choice`], which also invokes the [hooks](crate::core::Hook).
+    pub fn handle_choice(&mut self, key: InternalKey, index: u16) {
+        self.interacted = true;
+        self.ended = false;
         self.state.set_bit(key, index)
     }
 
+    /// Prefer [`Game::handle_action`], which also invokes the [hooks](crate::core::Hook).
     pub fn handle_action(&mut self, action: Action) -> Result<(), GameError> {
+        self.interacted = true;
+        self.ended = false;
         match action {
             Action::None => {}
-            Action::SetBit(k, v) => {
+            Action::SetBit(k, v) | Action::Timeout(k, v) => {
                 self.state.set_bit(k, v);
             }
             Action::Set(k, v) => {
@@ -161,25 +392,98 @@ impl GameInner {
             Action::Reset(k) => {
                 self.state.remove(&k);
             }
+            Action::SetText(k, v) => {
+                self.state.set_text(k, v);
+            }
             Action::Next(mut page) => {
                 page.id = "".into(); // Only rendered pages go into history + this is not the full name
                 self.pages.push(page)?;
             }
             Action::Back(n) => {
-                self.pages.pop_n(n)?;
+                let from = self.current_id();
+                self.pages.pop_n(n, &from)?;
             }
             Action::Tunnel(mut page) => {
                 self.pages.adv_stack();
+                self.tunnel_result = None;
                 page.id = "".into(); // Only rendered pages go into history + this is not the full name
                 self.pages.push(page)?;
             }
             Action::Exit => {
-                self.pages.pop_stack().ok_or(GameError::End)?;
+                let from = self.current_id();
+                self.pages.exit_tunnel(&from)?;
+                self.tunnel_result = None;
+            }
+            Action::SetVar(name, value) => {
+                self.vars.set(name, value);
+            }
+            Action::IncVar(name, by) => {
+                self.vars.inc(name, by);
+            }
+            Action::ToggleVar(name) => {
+                self.vars.toggle(name);
             }
         }
         Ok(())
     }
 
+    /// Time elapsed in game, see [`Game::tick`].
+    pub fn clock(&self) -> Duration {
+        self.clock
+    }
+
+    /// Time left until the next timer of the last view comes due.
+    pub fn next_timer(&self) -> Option<Duration> {
+        self.timers.iter().map(|t| t.remaining(self.clock)).min()
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The params of the page currently being called, if they are of type `P`.
+    pub fn params<P: std::any::Any>(&self) -> Option<&P> {
+        self.params.as_deref()?.downcast_ref()
+    }
+
+    /// The value passed to [`crate::EXIT`] by the last tunnel which returned to `pageid`.
+    pub fn tunnel_result(&self, pageid: &str) -> Option<TunnelValue> {
+        let (caller, value) = self.tunnel_result.as_ref()?;
+        (**caller == *pageid).then(|| value.clone())
+    }
+
+    /// Clear the [tunnel result](GameInner::tunnel_result) once a page other than the one it was returned to is viewed.
+    pub(crate) fn viewed_tunnel_result(&mut self, pageid: &PageId) {
+        if self
+            .tunnel_result
+            .as_ref()
+            .is_some_and(|(caller, _)| caller != pageid)
+        {
+            self.tunnel_result = None;
+        }
+    }
+
+    /// The id of the current page, or of the last page shown if the current one was not shown yet.
+    pub(crate) fn current_id(&self) -> PageId {
+        self.pages
+            .current()
+            .map(|p| p.id)
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| self.last_id.clone())
+    }
+
+    /// Whether `pageid` is the current page, in which case viewing it re-renders the same visit.
+    /// Threads take this from the page they are woven into.
+    pub fn showing(&self, pageid: &str) -> bool {
+        let pageid = self.weaving.first().map_or(pageid, |host| &**host);
+        self.pages.current().is_some_and(|p| *p.id == *pageid)
+    }
+
+    /// The seed given to a page's [`crate::core::PageState`].
+    pub fn page_seed(&self, pageid: &str) -> u64 {
+        mix_seed(self.seed, hash_str(pageid))
+    }
+
     /// Whether the last call to view changed the page
     pub fn fresh(&self) -> bool {
         self.fresh
@@ -194,7 +498,7 @@ impl GameInner {
     }
 }
 
-/// Instantiate a [`Game`] from a function decorated with [`crate::ifview`].
+/// Instantiate a [`Game`] from a function decorated with [`crate::ifview`], optionally with a [seed](Game::with_seed).
 ///
 /// # Example
 /// ```rust,ignore
@@ -210,6 +514,9 @@ macro_rules! Game {
     ($f:path) => {
         $crate::core::Game::new_with_page("", $f)
     };
+    ($f:path, $seed:expr) => {
+        $crate::core::Game::new_with_page("", $f).with_seed($seed)
+    };
 }
 
 // -------------- Page Stack -----------------
@@ -233,7 +540,9 @@ impl PageStack {
     }
 
     pub fn push(&mut self, page: PageHandle) -> Result<(), GameError> {
-        let stack = self.0.last_mut().ok_or(GameError::NoStack)?;
+        let Some(stack) = self.0.last_mut() else {
+            return Err(GameError::NoStack { page: page.id });
+        };
 
         let fresh = match stack.last() {
             Some(last) => last.id != page.id,
@@ -269,15 +578,27 @@ impl PageStack {
         self.0.last_mut()?.pop()
     }
 
-    /// Errors on n == 0
-    pub fn pop_n(&mut self, n: usize) -> Result<PageHandle, GameError> {
-        let stack = self.0.last_mut().ok_or(GameError::NoStack)?;
+    /// Go back n pages, `from` being the page going back.
+    /// Errors with [`GameError::BackBeyondHistory`] on n == 0, or if there are not more than n pages in the current stack frame.
+    pub fn pop_n(&mut self, n: usize, from: &PageId) -> Result<PageHandle, GameError> {
+        let frames = self.frames();
+        let stack = self
+            .0
+            .last_mut()
+            .ok_or_else(|| GameError::NoStack { page: from.clone() })?;
 
         if n < stack.len() && n > 0 {
             stack.truncate(stack.len() - n);
-            stack.last().cloned().ok_or(GameError::NoPage)
+            stack.last().cloned().ok_or_else(|| GameError::NoPage {
+                page: from.clone(),
+                frames,
+            })
         } else {
-            Err(GameError::NoPage)
+            Err(GameError::BackBeyondHistory {
+                page: from.clone(),
+                history: stack.len(),
+                n,
+            })
         }
     }
 
@@ -292,6 +613,40 @@ impl PageStack {
     pub fn pop_stack(&mut self) -> Option<Vec<PageHandle>> {
         self.0.pop()
     }
+
+    /// Pop the stack frame of the current tunnel, `from` being the page which exited.
+    /// Errors with [`GameError::TunnelExitWithoutTunnel`] if there is no enclosing tunnel.
+    pub fn exit_tunnel(&mut self, from: &PageId) -> Result<Vec<PageHandle>, GameError> {
+        if self.0.len() < 2 {
+            return Err(GameError::TunnelExitWithoutTunnel {
+                page: from.clone(),
+                history: self.len(),
+            });
+        }
+        self.pop_stack()
+            .ok_or_else(|| GameError::NoStack { page: from.clone() })
+    }
+
+    /// The number of stack frames, i.e. one more than the number of tunnels entered.
+    pub fn frames(&self) -> usize {
+        self.0.len()
+    }
+
+    // ------------------------------
+    // Persistence
+    // ------------------------------
+
+    /// The [`PageId`]s of each stack frame.
+    pub fn ids(&self) -> Vec<Vec<PageId>> {
+        self.0
+            .iter()
+            .map(|stack| stack.iter().map(|p| p.id.clone()).collect())
+            .collect()
+    }
+
+    pub fn from_frames(frames: Vec<Vec<PageHandle>>) -> Self {
+        Self(frames)
+    }
 }
 
 // ----------------------- BOILERPLATE ---------------------------------------------------
@@ -1,5 +1,6 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
     ops::{Deref, DerefMut},
 };
 
@@ -21,12 +22,7 @@ impl GameState {
     /// The click action uses [`crate::PageState::was_zero`] to run its closure exactly once.
     pub fn inc(&mut self, key: &InternalKey) {
         let (chapter_id, entry_key) = key;
-        let chapter = self
-            .inner
-            .entry(chapter_id.clone())
-            .or_insert_with(|| PageMap {
-                inner: HashMap::new(),
-            });
+        let chapter = self.inner.entry(chapter_id.clone()).or_default();
         chapter
             .inner
             .entry(entry_key.clone())
@@ -38,9 +34,7 @@ impl GameState {
     /// Creates the chapter if it does not exist.
     pub fn insert(&mut self, key: InternalKey, value: u64) {
         let (chapter_id, entry_key) = key;
-        let chapter = self.inner.entry(chapter_id).or_insert_with(|| PageMap {
-            inner: HashMap::new(),
-        });
+        let chapter = self.inner.entry(chapter_id).or_default();
         chapter.inner.insert(entry_key, value);
     }
 
@@ -49,29 +43,32 @@ impl GameState {
         let (chapter_id, entry_key) = key;
 
         if let Some(chapter) = self.inner.get_mut(chapter_id) {
-            chapter.inner.remove(&entry_key);
+            chapter.inner.remove(entry_key);
+            chapter.text.remove(entry_key);
+            chapter.selections.remove(entry_key);
 
             // Optional: remove chapter if it is now empty
-            if chapter.inner.is_empty() {
+            if chapter.is_empty() {
                 self.inner.remove(chapter_id);
             }
         }
     }
 
-    /// Treating the contained value as a bitmask, set the specified position to true.
+    /// Pick the specified position of the [`Selection`] at the given key.
     /// Creates the chapter or entry if it does not exist.
-    /// pos is u8 but max value should be 64
-    pub fn set_bit(&mut self, key: InternalKey, pos: u8) {
+    pub fn set_bit(&mut self, key: InternalKey, pos: u16) {
         let (chapter_id, entry_key) = key;
 
-        let chapter = self.inner.entry(chapter_id).or_insert_with(|| PageMap {
-            inner: HashMap::new(),
-        });
-
-        let current = chapter.inner.get(&entry_key).copied().unwrap_or(0);
-        let updated = current | (1 << pos);
+        let chapter = self.inner.entry(chapter_id).or_default();
+        chapter.selections.entry(entry_key).or_default().pick(pos);
+    }
 
-        chapter.inner.insert(entry_key, updated);
+    /// Store a string at the given key, see [`crate::view::Object::Input`].
+    /// Creates the chapter if it does not exist.
+    pub fn set_text(&mut self, key: InternalKey, value: String) {
+        let (chapter_id, entry_key) = key;
+        let chapter = self.inner.entry(chapter_id).or_default();
+        chapter.text.insert(entry_key, value);
     }
 
     /// Get a reference to the chapter state for a given chapter ID.
@@ -87,11 +84,174 @@ impl GameState {
 
 // --------------------------------------------------------
 
-/// Nothing more than a Hashmap
+/// Nothing more than a Hashmap, plus maps for strings and choice selections, and the once tags fired on the page
 #[derive(Debug, Default, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PageMap {
     inner: HashMap<PageKey, u64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    text: HashMap<PageKey, String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    selections: HashMap<PageKey, Selection>,
+    /// The [`crate::view::TagKind::Once`] tags which fired on the current visit, kept apart from the element keys
+    #[cfg_attr(feature = "serde", serde(default))]
+    once: HashSet<PageId>,
+}
+
+impl PageMap {
+    pub fn get_text(&self, key: &PageKey) -> Option<&String> {
+        self.text.get(key)
+    }
+
+    pub fn insert_text(&mut self, key: PageKey, value: String) -> Option<String> {
+        self.text.insert(key, value)
+    }
+
+    pub fn remove_text(&mut self, key: &PageKey) -> Option<String> {
+        self.text.remove(key)
+    }
+
+    pub fn get_selection(&self, key: &PageKey) -> Option<&Selection> {
+        self.selections.get(key)
+    }
+
+    /// Creates the selection if it does not exist.
+    pub fn selection_mut(&mut self, key: PageKey) -> &mut Selection {
+        self.selections.entry(key).or_default()
+    }
+
+    pub fn remove_selection(&mut self, key: &PageKey) -> Option<Selection> {
+        self.selections.remove(key)
+    }
+
+    pub fn contains_once(&self, tag: &PageId) -> bool {
+        self.once.contains(tag)
+    }
+
+    pub fn insert_once(&mut self, tag: PageId) -> bool {
+        self.once.insert(tag)
+    }
+
+    pub fn remove_once(&mut self, tag: &PageId) -> bool {
+        self.once.remove(tag)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+            && self.text.is_empty()
+            && self.selections.is_empty()
+            && self.once.is_empty()
+    }
+}
+
+/// The state of a set of choices, see [`crate::view::Object::Choice`].
+///
+/// Which options are selected is stored as a growable bitset, so there is no limit on the number of options.
+/// The options which were picked are also recorded in the order of their latest pick, with the number of times each was picked,
+/// so pages can ask what was picked second, or how many times an option was picked.
+///
+/// The bitset is kept without trailing empty words, so equal selections compare and [hash](crate::Game::fingerprint) the same.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Selection {
+    bits: Vec<u64>,
+    /// Each picked option once, with the number of times it was picked
+    picks: Vec<(u16, usize)>,
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.bits
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    /// Mark an option as selected, without recording a pick.
+    pub fn set(&mut self, index: usize) {
+        let word = index / 64;
+        if word >= self.bits.len() {
+            self.bits.resize(word + 1, 0);
+        }
+        self.bits[word] |= 1 << (index % 64);
+    }
+
+    /// Mark an option as not selected. Its recorded picks are kept.
+    pub fn unset(&mut self, index: usize) {
+        if let Some(word) = self.bits.get_mut(index / 64) {
+            *word &= !(1 << (index % 64));
+        }
+        while self.bits.last() == Some(&0) {
+            self.bits.pop();
+        }
+    }
+
+    /// Mark every option as not selected. The recorded picks are kept.
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Select an option and record the pick.
+    pub fn pick(&mut self, index: u16) {
+        self.set(index as usize);
+        let count = match self.picks.iter().position(|(i, _)| *i == index) {
+            Some(at) => self.picks.remove(at).1,
+            None => 0,
+        };
+        self.picks.push((index, count + 1));
+    }
+
+    /// Whether no option is selected.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    /// The selected options, in ascending order.
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits.iter().enumerate().flat_map(|(w, word)| {
+            (0..64)
+                .filter(move |i| word & (1 << i) != 0)
+                .map(move |i| w * 64 + i)
+        })
+    }
+
+    pub fn mask<const N: usize>(&self) -> [bool; N] {
+        std::array::from_fn(|i| self.contains(i))
+    }
+
+    pub fn mask_vec(&self, n: usize) -> Vec<bool> {
+        (0..n).map(|i| self.contains(i)).collect()
+    }
+
+    /// The most recently picked option which is still selected.
+    /// Falls back to the lowest selected option for selections without picks (such as those set by `set_key_mask!`).
+    pub fn last(&self) -> Option<u16> {
+        self.picks()
+            .rev()
+            .find(|i| self.contains(*i as usize))
+            .or_else(|| self.indices().next().map(|i| i as u16))
+    }
+
+    /// The `n`-th (0-indexed) of the picked options, ordered by their latest pick.
+    pub fn nth(&self, n: usize) -> Option<u16> {
+        self.picks.get(n).map(|(i, _)| *i)
+    }
+
+    /// How many times an option was picked.
+    pub fn count(&self, index: u16) -> usize {
+        self.picks
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// The picked options, ordered by their latest pick.
+    pub fn picks(&self) -> impl DoubleEndedIterator<Item = u16> + '_ {
+        self.picks.iter().map(|(i, _)| *i)
+    }
 }
 
 pub type InternalKey = (PageId, PageKey);
@@ -108,6 +268,31 @@ impl GameState {
     }
 }
 
+/// Hashes are independent of map order, and pages without state are skipped, see [`crate::Game::fingerprint`].
+impl Hash for GameState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut pages: Vec<_> = self.inner.iter().filter(|(_, m)| !m.is_empty()).collect();
+        pages.sort_unstable_by(|a, b| a.0.0.cmp(&b.0.0));
+        pages.hash(state);
+    }
+}
+
+impl Hash for PageMap {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        fn sorted<V>(map: &HashMap<PageKey, V>) -> Vec<(&PageKey, &V)> {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by_key(|(k, _)| **k);
+            entries
+        }
+        sorted(&self.inner).hash(state);
+        sorted(&self.text).hash(state);
+        sorted(&self.selections).hash(state);
+        let mut once: Vec<_> = self.once.iter().map(|t| &*t.0).collect();
+        once.sort_unstable();
+        once.hash(state);
+    }
+}
+
 impl Deref for PageMap {
     type Target = HashMap<PageKey, u64>;
     fn deref(&self) -> &Self::Target {
@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+
+use crate::Game;
+use crate::core::{GameContext, GameInner, GameTags, PageId};
+
+/// The state of a [`Game`] as of a call to [`Game::view`].
+#[derive(Debug, Clone)]
+pub struct Snapshot<C> {
+    pub inner: GameInner,
+    pub context: C,
+    pub tags: GameTags,
+}
+
+impl<C> Snapshot<C> {
+    /// The page which was viewed.
+    pub fn id(&self) -> &PageId {
+        &self.inner.last_id
+    }
+}
+
+/// A bounded undo/redo history, see [`Game::undo`].
+///
+/// A snapshot is recorded whenever [`Game::view`] produces a fresh page, or follows an interaction.
+#[derive(Debug, Clone)]
+pub struct History<C> {
+    past: VecDeque<Snapshot<C>>,
+    future: Vec<Snapshot<C>>,
+    capacity: usize,
+}
+
+impl<C> History<C> {
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            past: VecDeque::new(),
+            future: Vec::new(),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Snapshots which can be returned to, oldest first. The last one is the current state.
+    pub fn past(&self) -> impl DoubleEndedIterator<Item = &Snapshot<C>> {
+        self.past.iter()
+    }
+
+    /// Snapshots which have been undone, most recent first.
+    pub fn future(&self) -> impl DoubleEndedIterator<Item = &Snapshot<C>> {
+        self.future.iter().rev()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.past.len() > 1
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+
+    fn record(&mut self, snapshot: Snapshot<C>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.future.clear();
+        if self.past.len() == self.capacity {
+            self.past.pop_front();
+        }
+        self.past.push_back(snapshot);
+    }
+}
+
+impl<C: GameContext> Game<C> {
+    /// Set the number of snapshots kept for [`Game::undo`]. 0 disables history.
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.history = History::new(capacity);
+        self
+    }
+
+    pub fn history(&self) -> &History<C> {
+        &self.history
+    }
+
+    /// Called by [`Game::view`]
+    pub(crate) fn record_history(&mut self) {
+        if !(self.inner.fresh || self.inner.interacted) || self.simulating {
+            return;
+        }
+        self.inner.interacted = false;
+
+        let snapshot = Snapshot {
+            inner: self.inner.clone(),
+            context: self.context.clone(),
+            tags: self.tags.clone(),
+        };
+        self.history.record(snapshot);
+    }
+
+    /// Return to the state of the previous snapshot, including the page stack.
+    /// Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if !self.history.can_undo() {
+            return false;
+        }
+        let current = self.history.past.pop_back().unwrap();
+        self.history.future.push(current);
+        let previous = self.history.past.back().unwrap().clone();
+        self.apply(previous);
+        true
+    }
+
+    /// Reapply the last undone snapshot.
+    /// Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.history.future.pop() else {
+            return false;
+        };
+        self.history.past.push_back(next.clone());
+        self.apply(next);
+        true
+    }
+
+    fn apply(&mut self, snapshot: Snapshot<C>) {
+        let Snapshot {
+            inner,
+            context,
+            tags,
+        } = snapshot;
+        self.inner = inner;
+        self.context = context;
+        self.tags = tags;
+    }
+}
+
+// ----------------------- BOILERPLATE ---------------------------------------------------
+
+impl<C> Default for History<C> {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::core::game_state::InternalKey;
+use crate::core::{GameContext, PageId};
+use crate::{Action, Game, GameError, View};
+
+/// Cross-cutting logic which runs on every page, registered with [`Game::with_hook`].
+///
+/// All methods default to doing nothing. Hooks are invoked as the game changes state, both in play and in the [simulation](Game::simulate), in the order they were registered:
+/// - [`Hook::on_action`] for each action as it is handled by [`Game::handle_action`] or [`Game::handle_choice`].
+/// - [`Hook::on_leave_page`] and [`Hook::on_enter_page`] whenever [`Game::view`] resolves a different page than the last one it viewed,
+///   whether it was reached by an action (i.e. [`Action::Next`]), or by a page responding with a link, tunnel, exit or back.
+///   As the page is only known by its view, it is entered after being called, so changes to the context show up from its next view.
+///   The page the game starts on, and the page a [restored](Game::restore) game was saved on, are entered on their first view, without a page to leave.
+/// - [`Hook::on_view`] for every view returned by [`Game::view`], including those re-rendering the same page (i.e. every frame), so it should only adjust the view.
+/// - [`Hook::on_end`] once the game ends.
+///
+/// Hooks are not saved, and must be registered again after restoring a game.
+///
+/// # Example
+/// ```rust,ignore
+/// struct Days;
+///
+/// impl Hook<State> for Days {
+///     fn on_enter_page(&self, game: &mut Game<State>, _page: &PageId) {
+///         game.context.days += 1;
+///     }
+/// }
+///
+/// pub fn new() -> Game {
+///     ifengine::Game!(chap1::p1).with_hook(Days)
+/// }
+/// ```
+#[allow(unused_variables)]
+pub trait Hook<C>: Send + Sync + 'static {
+    fn on_enter_page(&self, game: &mut Game<C>, page: &PageId) {}
+    fn on_leave_page(&self, game: &mut Game<C>, page: &PageId) {}
+    fn on_action(&self, game: &mut Game<C>, action: &Action) {}
+    fn on_view(&self, game: &mut Game<C>, view: &mut View) {}
+    fn on_end(&self, game: &mut Game<C>) {}
+}
+
+/// The [hooks](Hook) registered on a [`Game`].
+pub struct Hooks<C>(Vec<Arc<dyn Hook<C>>>);
+
+impl<C: GameContext> Game<C> {
+    pub fn with_hook(mut self, hook: impl Hook<C>) -> Self {
+        self.add_hook(hook);
+        self
+    }
+
+    pub fn add_hook(&mut self, hook: impl Hook<C>) {
+        self.hooks.0.push(Arc::new(hook));
+    }
+
+    /// Handle an action, then invoke [`Hook::on_action`].
+    /// If it moves to another page, the page hooks fire once that page is viewed.
+    pub fn handle_action(&mut self, action: Action) -> Result<(), GameError> {
+        self.inner.handle_action(action.clone())?;
+        for hook in self.hooks.clone().iter() {
+            hook.on_action(self, &action);
+        }
+        Ok(())
+    }
+
+    /// Select a choice, then invoke [`Hook::on_action`] with the equivalent [`Action::SetBit`].
+    pub fn handle_choice(&mut self, key: InternalKey, index: u16) {
+        self.inner.handle_choice(key.clone(), index);
+        let action = Action::SetBit(key, index);
+        for hook in self.hooks.clone().iter() {
+            hook.on_action(self, &action);
+        }
+    }
+
+    /// Called by [`Game::view`] and the simulation when the resolved page changes, see [`Game::visit`].
+    pub(crate) fn hook_pages(&mut self, from: Option<&PageId>, to: &PageId) {
+        let hooks = self.hooks.clone();
+        // the first view of the game has no page to come from
+        if let Some(from) = from.filter(|from| !from.is_empty()) {
+            for hook in hooks.iter() {
+                hook.on_leave_page(self, from);
+            }
+        }
+        for hook in hooks.iter() {
+            hook.on_enter_page(self, to);
+        }
+    }
+
+    pub(crate) fn hook_view(&mut self, view: &mut View) {
+        for hook in self.hooks.clone().iter() {
+            hook.on_view(self, view);
+        }
+    }
+
+    pub(crate) fn hook_end(&mut self) {
+        for hook in self.hooks.clone().iter() {
+            hook.on_end(self);
+        }
+    }
+}
+
+// ----------------------- BOILERPLATE ---------------------------------------------------
+
+impl<C> Default for Hooks<C> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<C> Clone for Hooks<C> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<C> std::fmt::Debug for Hooks<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Hooks({})", self.0.len())
+    }
+}
+
+impl<C> std::ops::Deref for Hooks<C> {
+    type Target = Vec<Arc<dyn Hook<C>>>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
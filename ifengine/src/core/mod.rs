@@ -8,6 +8,8 @@ pub use page_state::*;
 
 // Global state
 pub mod game_state;
+mod vars;
+pub use vars::*;
 
 // app, state -(guide)-> run -> view -(composed)-> element
 mod game;
@@ -15,3 +17,23 @@ pub use game::*;
 
 mod action;
 pub use action::*;
+
+mod save;
+pub use save::*;
+
+mod history;
+pub use history::*;
+
+mod storylet;
+pub use storylet::*;
+
+mod achievements;
+pub use achievements::*;
+
+mod hooks;
+pub use hooks::*;
+
+#[cfg(feature = "macros")]
+mod registry;
+#[cfg(feature = "macros")]
+pub use registry::*;
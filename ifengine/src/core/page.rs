@@ -1,10 +1,12 @@
 use std::any::Any;
 use std::fmt;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
-use crate::Game;
 use crate::core::GameContext;
+use crate::utils::hash_value;
 use crate::view::View;
+use crate::{Game, GameError};
 
 /// Static functions. These implement [`PageErased`]
 /// You can create one by annotating a bare fn(&mut C) with (`[#ifview]`)[crate::ifview]
@@ -12,7 +14,7 @@ pub type Page<C> = fn(&mut Game<C>) -> Response;
 
 /// The trait which defines a page
 /// Capable of (eventually) producing a [`View`] when (repeatedly) called by [`Game::view`]
-pub trait PageErased: Send + Sync + 'static {
+pub trait PageErased: Any + Send + Sync {
     fn call(&self, game: &mut dyn Any) -> Response;
 }
 
@@ -22,7 +24,55 @@ impl<C: GameContext> PageErased for Page<C> {
         self(game)
     }
 }
-// todo: add struct impl
+
+/// A page which can carry its own data, i.e. one generated at runtime.
+/// Implemented for closures taking a `&mut Game<C>`.
+///
+/// Wrap one into a [`PageHandle`] with [`PageHandle::new_object`].
+///
+/// # Example
+/// ```rust,ignore
+/// struct Shop {
+///     stock: Vec<Item>,
+/// }
+///
+/// impl PageObject<State> for Shop {
+///     fn call(&self, game: &mut Game<State>) -> Response {
+///         let mut view = View::new("shop".into());
+///         for item in &self.stock {
+///             view.inner.push(Object::Paragraph(item.name.clone().into()));
+///         }
+///         Response::View(view)
+///     }
+/// }
+///
+/// let page = PageHandle::new_object("shop".into(), Shop { stock });
+/// ```
+pub trait PageObject<C>: Send + Sync + 'static {
+    fn call(&self, game: &mut Game<C>) -> Response;
+}
+
+impl<C, F> PageObject<C> for F
+where
+    F: Fn(&mut Game<C>) -> Response + Send + Sync + 'static,
+{
+    fn call(&self, game: &mut Game<C>) -> Response {
+        self(game)
+    }
+}
+
+/// Implements [`PageErased`] for a [`PageObject`], see [`PageHandle::new_object`]
+struct ObjectPage<C, T> {
+    object: T,
+    _context: PhantomData<fn(&mut C)>,
+}
+
+impl<C: GameContext, T: PageObject<C>> PageErased for ObjectPage<C, T> {
+    fn call(&self, game: &mut dyn Any) -> Response {
+        let game = game.downcast_mut::<Game<C>>().expect("Game type mismatch");
+        self.object.call(game)
+    }
+}
 
 // newtype over alias just because arc doesn't have serialize, this is very annoying
 // On the plus side makes typing a bit stronger...
@@ -36,6 +86,16 @@ impl PageId {
     pub fn clear(&mut self) {
         self.0 = "".into()
     }
+
+    /// The id of `page` when called with `params`, see [`PageHandle::with_params`].
+    pub fn with_params(page: &str, params: &impl std::hash::Hash) -> Self {
+        format!("{page}#{:016x}", hash_value(params)).into()
+    }
+
+    /// Whether this is the id of a page called with params, see [`PageId::with_params`].
+    pub fn has_params(&self) -> bool {
+        self.0.contains('#')
+    }
 }
 
 /// The type returned by a [`PageErased`]
@@ -46,14 +106,28 @@ pub enum Response {
     Back(usize),
     Tunnel(PageHandle),
     Exit,
+    /// Exit the current tunnel, passing a value to the page which entered it, see [`crate::EXIT`].
+    ExitWith(TunnelValue),
     End, // thread?
+    /// Stop with an error, i.e. when a page is called without the params it expects.
+    Error(GameError),
+    /// A view with threads to merge in, see [`crate::elements::weave`].
+    /// Each thread is inserted at the given object index of the view.
+    Weave(View, Vec<(usize, PageHandle)>),
 }
 
+/// Arguments passed to a page, see [`PageHandle::with_params`]
+pub type Params = Arc<dyn Any + Send + Sync>;
+
+/// A value returned from a tunnel, see [`Response::ExitWith`]
+pub type TunnelValue = Arc<dyn Any + Send + Sync>;
+
 /// Capable of (eventually) producing a [`View`]
 #[derive(Clone)]
 pub struct PageHandle {
     pub widget: Arc<dyn PageErased>,
     pub id: PageId,
+    pub params: Option<Params>,
 }
 
 impl iddqd::IdHashItem for PageHandle {
@@ -71,6 +145,7 @@ impl PageHandle {
         Self {
             widget: Arc::new(widget), // no closure needed
             id,
+            params: None,
         }
     }
 
@@ -78,16 +153,69 @@ impl PageHandle {
         Self {
             widget: Arc::new(widget.into()), // no closure needed
             id,
+            params: None,
         }
     }
 
+    /// Wrap a [`PageObject`], such as a struct or closure.
+    pub fn new_object<C: GameContext>(id: PageId, object: impl PageObject<C>) -> Self {
+        Self {
+            widget: Arc::new(ObjectPage {
+                object,
+                _context: PhantomData,
+            }),
+            id,
+            params: None,
+        }
+    }
+
+    /// Attach arguments for the page, which it receives as the second parameter of an [`crate::ifview`] function.
+    ///
+    /// The params are kept with the handle, so returning to it (i.e. with [`Response::Back`]) calls the page with the same arguments.
+    pub fn with_params<P: Any + Send + Sync>(mut self, params: P) -> Self {
+        self.params = Some(Arc::new(params));
+        self
+    }
+
+    /// The function behind this handle, if it wraps a [`Page`] with context `C`.
+    pub fn page<C: GameContext>(&self) -> Option<Page<C>> {
+        (&*self.widget as &dyn Any).downcast_ref().copied()
+    }
+
     pub fn call<C: GameContext>(&self, game: &mut Game<C>) -> Response {
+        game.inner.params = self.params.clone();
         self.widget.call(game as &mut dyn Any)
     }
 }
 
 // ----------------------- BOILERPLATE ---------------------------------------------------
 
+/// Handles are equal if they share the id, page and params, the latter two being compared by identity.
+impl PartialEq for PageHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && Arc::ptr_eq(&self.widget, &other.widget)
+            && match (&self.params, &other.params) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+impl Eq for PageHandle {}
+
+impl std::hash::Hash for PageHandle {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        Arc::as_ptr(&self.widget).cast::<()>().hash(state);
+        self.params
+            .as_ref()
+            .map(|p| Arc::as_ptr(p).cast::<()>())
+            .hash(state);
+    }
+}
+
 impl fmt::Debug for PageHandle {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Page").field("name", &self.id).finish()
@@ -1,11 +1,11 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, rc::Rc, time::Duration};
 
 use crate::{
     core::{
-        GameTags, PageId, Response,
-        game_state::{PageKey, PageMap},
+        GameTags, PageHandle, PageId, Response, TunnelValue,
+        game_state::{PageKey, PageMap, Selection},
     },
-    view::{Object, View},
+    view::{Object, TagKind, View},
 };
 
 /// The [`crate::ifview`] decorator instantiates this from a reference to [`struct@crate::Game`], using it to add [elements](crate::elements) which read and write to [`crate::core::game_state::GameState`].
@@ -14,12 +14,22 @@ use crate::{
 pub struct PageState<'a> {
     view: View,
     page_state: RefCell<&'a mut PageMap>, // to allow simultaneous method accesses, safe because chapter_state doesn't produce refs
-    #[cfg(feature = "rand")]
-    pub seed: Option<u64>,
+    /// See [`crate::core::GameInner::page_seed`]
+    seed: u64,
     fresh: bool,
+    /// Whether the page is already shown, i.e. this view re-renders the same visit, see [`crate::core::GameInner::showing`]
+    showing: bool,
     game_tags: &'a mut GameTags,
     /// [`crate::Game::simulate`]
     pub simulating: bool,
+    /// Indices of the enclosing `key_scope!`s
+    scope: Rc<RefCell<Vec<u64>>>,
+    /// Pages to weave into the view, with the object index at which they were added
+    threads: Vec<(usize, PageHandle)>,
+    /// See [`crate::core::GameInner::tunnel_result`]
+    tunnel_result: Option<TunnelValue>,
+    /// See [`crate::core::GameInner::clock`]
+    clock: Duration,
 }
 
 impl<'a> PageState<'a> {
@@ -33,16 +43,67 @@ impl<'a> PageState<'a> {
         Self {
             view: View::new(name.into()),
             page_state: RefCell::new(page_state),
-            #[cfg(feature = "rand")]
-            seed: None,
+            seed: 0,
             fresh,
+            showing: false,
             game_tags,
             simulating,
+            scope: Default::default(),
+            threads: Vec::new(),
+            tunnel_result: None,
+            clock: Duration::ZERO,
         }
     }
 }
 
 impl<'a> PageState<'a> {
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn with_showing(mut self, showing: bool) -> Self {
+        self.showing = showing;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Duration) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn clock(&self) -> Duration {
+        self.clock
+    }
+
+    /// The point on the game clock at which `timeout` has passed since the key was reached on the current visit.
+    /// The start is stored under the key, and restarted whenever the page is entered anew, see [`crate::view::Timer`].
+    pub fn deadline(&self, key: PageKey, timeout: Duration) -> Duration {
+        let start = match self.get(key) {
+            Some(start) if self.showing => start,
+            _ => {
+                let now = self.clock.as_millis() as u64;
+                self.insert(key, now);
+                now
+            }
+        };
+        Duration::from_millis(start).saturating_add(timeout)
+    }
+
+    pub fn with_tunnel_result(mut self, value: Option<TunnelValue>) -> Self {
+        self.tunnel_result = value;
+        self
+    }
+
+    /// The value returned by the last tunnel entered from this page, if it is of type `T`.
+    pub fn tunnel_result<T: std::any::Any + Clone>(&self) -> Option<T> {
+        self.tunnel_result.as_deref()?.downcast_ref().cloned()
+    }
+
     pub fn push(&mut self, object: Object) {
         self.view.push(object);
     }
@@ -52,72 +113,106 @@ impl<'a> PageState<'a> {
     }
 
     pub fn into_response(self) -> Response {
-        Response::View(self.view)
+        if self.threads.is_empty() {
+            Response::View(self.view)
+        } else {
+            Response::Weave(self.view, self.threads)
+        }
+    }
+
+    /// Merge the view of another page into this one, at the current position.
+    pub fn weave(&mut self, thread: PageHandle) {
+        self.threads.push((self.view.len(), thread));
     }
 
     pub fn fresh(&self) -> bool {
         self.fresh
     }
 
+    // --------- Key scopes
+    /// Offset keys by `index` until the returned guard is dropped.
+    pub fn push_scope(&self, index: u64) -> ScopeGuard {
+        self.scope.borrow_mut().push(index);
+        ScopeGuard(self.scope.clone())
+    }
+
+    /// Offset a key by the enclosing scopes, or return it unchanged if there are none.
+    pub fn scoped(&self, key: PageKey) -> PageKey {
+        self.scope.borrow().iter().fold(key, |acc, index| {
+            (acc ^ index.wrapping_add(0x9e3779b97f4a7c15)).wrapping_mul(0x100000001b3)
+        })
+    }
+
     // --------- Chapter state
     // indexing takes owned for convenience (PageKey is copy)
     pub fn get(&self, key: PageKey) -> Option<u64> {
         self.page_state.borrow().get(&key).copied()
     }
 
-    pub fn get_mask_indices(&self, key: PageKey) -> Vec<usize> {
-        let val = match self.page_state.borrow().get(&key).copied() {
-            Some(v) => v,
-            None => return Vec::new(),
-        };
+    /// The [`Selection`] at a key, see [`crate::view::Object::Choice`].
+    pub fn selection(&self, key: PageKey) -> Option<Selection> {
+        self.page_state.borrow().get_selection(&key).cloned()
+    }
 
-        let mut bits = Vec::new();
-        for i in 0..64 {
-            if (val & (1 << i)) != 0 {
-                bits.push(i);
-            }
+    pub fn get_mask_indices(&self, key: PageKey) -> Vec<usize> {
+        match self.page_state.borrow().get_selection(&key) {
+            Some(s) => s.indices().collect(),
+            None => Vec::new(),
         }
-        bits
     }
 
     pub fn get_mask<const N: usize>(&self, key: PageKey) -> [bool; N] {
-        let val = match self.page_state.borrow().get(&key).copied() {
-            Some(v) => v,
-            None => return [false; N],
-        };
+        match self.page_state.borrow().get_selection(&key) {
+            Some(s) => s.mask(),
+            None => [false; N],
+        }
+    }
 
-        std::array::from_fn(|i| i < 64 && (val & (1 << i)) != 0)
+    pub fn get_mask_vec(&self, key: PageKey, n: usize) -> Vec<bool> {
+        match self.page_state.borrow().get_selection(&key) {
+            Some(s) => s.mask_vec(n),
+            None => vec![false; n],
+        }
     }
 
-    pub fn get_mask_last(&self, key: PageKey) -> Option<u8> {
-        let val = match self.page_state.borrow().get(&key).copied() {
-            Some(v) => v,
-            None => return None,
-        };
+    pub fn get_mask_last(&self, key: PageKey) -> Option<u16> {
+        self.page_state.borrow().get_selection(&key)?.last()
+    }
 
-        if val == 0 {
-            None
-        } else {
-            Some(val.trailing_zeros() as u8)
-        }
+    /// Deselect every option at a key, returning the last one picked.
+    /// The recorded picks are kept, so [`Selection::count`] keeps counting across clicks.
+    pub fn take_mask_last(&mut self, key: PageKey) -> Option<u16> {
+        let mut page_state = self.page_state.borrow_mut();
+        let selection = page_state.selection_mut(key);
+        let last = selection.last();
+        selection.clear();
+        last
     }
 
-    pub fn remove_mask_last(&mut self, key: PageKey) -> Option<u8> {
-        let val = match self.page_state.borrow_mut().remove(&key) {
-            Some(v) => v,
-            None => return None,
-        };
+    /// Select the given options of a [`Selection`], without recording picks.
+    pub fn set_mask(&self, key: PageKey, indices: &[usize]) {
+        let mut page_state = self.page_state.borrow_mut();
+        let selection = page_state.selection_mut(key);
+        for i in indices {
+            selection.set(*i);
+        }
+    }
 
-        if val == 0 {
-            None
-        } else {
-            Some(val.trailing_zeros() as u8)
+    pub fn unset_mask(&self, key: PageKey, indices: &[usize]) {
+        let mut page_state = self.page_state.borrow_mut();
+        let selection = page_state.selection_mut(key);
+        for i in indices {
+            selection.unset(*i);
         }
     }
 
+    /// Pick a random index in `0..range`, not in `exclude`.
+    ///
+    /// The result is determined by the page seed, the key, and the key's current value,
+    /// so it is reproduced exactly when replaying from the same state.
     #[cfg(feature = "rand")]
-    pub fn rand(&self, range: usize, exclude: &[usize]) -> usize {
-        use rand::{SeedableRng, rngs::StdRng, seq::IndexedRandom};
+    pub fn rand(&self, key: PageKey, range: usize, exclude: &[usize]) -> usize {
+        use crate::utils::{mix_seed, pick};
 
         let excl: std::collections::HashSet<usize> = exclude.iter().copied().collect();
 
@@ -127,19 +222,32 @@ impl<'a> PageState<'a> {
             panic!("rand(): range exhausted by exclusion list");
         }
 
-        if let Some(seed) = self.seed {
-            *pool.choose(&mut StdRng::seed_from_u64(seed)).unwrap()
-        } else {
-            *pool.choose(&mut rand::rng()).unwrap()
-        }
+        let seed = mix_seed(mix_seed(self.seed, key), self.get(key).unwrap_or(u64::MAX));
+        pool[pick(seed, pool.len()).unwrap()]
     }
 
     pub fn insert(&self, key: PageKey, value: u64) {
         self.page_state.borrow_mut().insert(key, value);
     }
 
+    /// Also removes the [`Selection`] and text at the key.
     pub fn remove(&self, key: PageKey) -> Option<u64> {
-        self.page_state.borrow_mut().remove(&key)
+        let mut page_state = self.page_state.borrow_mut();
+        page_state.remove_selection(&key);
+        page_state.remove_text(&key);
+        page_state.remove(&key)
+    }
+
+    pub fn get_text(&self, key: PageKey) -> Option<String> {
+        self.page_state.borrow().get_text(&key).cloned()
+    }
+
+    pub fn insert_text(&self, key: PageKey, value: String) {
+        self.page_state.borrow_mut().insert_text(key, value);
+    }
+
+    pub fn remove_text(&self, key: PageKey) -> Option<String> {
+        self.page_state.borrow_mut().remove_text(&key)
     }
 
     pub fn was_zero(&self, key: PageKey) -> bool {
@@ -153,10 +261,37 @@ impl<'a> PageState<'a> {
         }
     }
 
-    pub fn tag(&mut self, s: &str) -> bool {
+    /// Tag the current view, see [`TagKind`].
+    /// Returns false if a [`TagKind::Once`] tag fired on an earlier visit, or a [`TagKind::Sticky`] tag was already present.
+    pub fn tag(&mut self, s: &str, kind: TagKind) -> bool {
         let q: PageId = s.into();
-        self.view.tags.push(q.clone());
-        self.game_tags.insert(q)
+        match kind {
+            TagKind::View => {
+                self.view.tags.push((q, kind));
+                true
+            }
+            TagKind::Sticky => {
+                self.view.tags.push((q.clone(), kind));
+                self.game_tags.insert(q)
+            }
+            TagKind::Once => {
+                // remembered on the page, so that re-rendering the visit it fired on keeps it
+                let mut page_state = self.page_state.borrow_mut();
+                let fired = if self.game_tags.insert(q.clone()) {
+                    page_state.insert_once(q.clone());
+                    true
+                } else if self.showing {
+                    page_state.contains_once(&q)
+                } else {
+                    page_state.remove_once(&q);
+                    false
+                };
+                if fired {
+                    self.view.tags.push((q, kind));
+                }
+                fired
+            }
+        }
     }
 
     pub fn untag(&mut self, s: &str) -> bool {
@@ -164,6 +299,16 @@ impl<'a> PageState<'a> {
     }
 }
 
+/// Ends a `key_scope!` when dropped, so that it doesn't outlive a `break`, `return` or panic in its body.
+#[derive(Debug)]
+pub struct ScopeGuard(Rc<RefCell<Vec<u64>>>);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        self.0.borrow_mut().pop();
+    }
+}
+
 // ------------- BOILERPLATE
 use std::fmt;
 
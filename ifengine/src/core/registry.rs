@@ -0,0 +1,135 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use crate::core::{GameContext, GameSave, Page, PageHandle, PageId};
+use crate::run::Simulation;
+use crate::{Game, GameError};
+
+/// An entry submitted by [`crate::ifview`] for each decorated function.
+///
+/// Not meant to be constructed by hand, see [`Registry`] instead.
+#[doc(hidden)]
+pub struct RegisteredPage {
+    pub id: &'static str,
+    /// A `&'static Page<C>`, erased so that pages of every context share one collection.
+    pub page: &'static (dyn Any + Send + Sync),
+}
+
+inventory::collect!(RegisteredPage);
+
+/// Every [`RegisteredPage`] by id, collected on first use.
+fn registered() -> &'static BTreeMap<&'static str, &'static (dyn Any + Send + Sync)> {
+    static PAGES: OnceLock<BTreeMap<&'static str, &'static (dyn Any + Send + Sync)>> =
+        OnceLock::new();
+    PAGES.get_or_init(|| {
+        inventory::iter::<RegisteredPage>
+            .into_iter()
+            .map(|r| (r.id, r.page))
+            .collect()
+    })
+}
+
+/// Every [page](Page) declared with [`crate::ifview`] for the context `C`, keyed by id (`module_path::name`).
+///
+/// # Example
+/// ```rust,ignore
+/// let registry = Registry::<State>::new();
+/// for id in registry.ids() {
+///     println!("{id}");
+/// }
+///
+/// game.jump("story::chap1::p3")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Registry<C> {
+    pages: BTreeMap<&'static str, Page<C>>,
+}
+
+impl<C: GameContext> Registry<C> {
+    /// Collect the registered pages with context `C`.
+    pub fn new() -> Self {
+        let pages = registered()
+            .iter()
+            .filter_map(|(&id, page)| Some((id, *page.downcast_ref::<Page<C>>()?)))
+            .collect();
+
+        Self { pages }
+    }
+
+    /// Look up a single page, without collecting the others.
+    fn lookup(id: &str) -> Option<Page<C>> {
+        registered().get(id)?.downcast_ref().copied()
+    }
+
+    /// The id a page was registered under, found by comparing functions.
+    pub(crate) fn id_of(page: Page<C>) -> Option<&'static str> {
+        registered().iter().find_map(|(&id, registered)| {
+            let registered = *registered.downcast_ref::<Page<C>>()?;
+            std::ptr::fn_addr_eq(registered, page).then_some(id)
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<Page<C>> {
+        self.pages.get(id).copied()
+    }
+
+    pub fn handle(&self, id: &str) -> Option<PageHandle> {
+        self.get(id).map(|page| PageHandle::new(id.into(), page))
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.pages.contains_key(id)
+    }
+
+    /// Registered ids, in sorted order.
+    pub fn ids(&self) -> impl Iterator<Item = PageId> + '_ {
+        self.pages.keys().map(|&id| id.into())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, Page<C>)> + '_ {
+        self.pages.iter().map(|(&id, &page)| (id, page))
+    }
+
+    /// Registered pages which were never viewed during the [simulation](Game::simulate).
+    pub fn unreachable(&self, simulation: &Simulation) -> Vec<PageId> {
+        self.ids()
+            .filter(|id| !simulation.runs.values().any(|r| r.contains_key(id)))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+}
+
+impl<C: GameContext> Game<C> {
+    /// Instantiate a [`Game`] from a registered page id.
+    pub fn new_with_id(id: &str) -> Result<Self, GameError> {
+        let page = Registry::<C>::lookup(id).ok_or_else(|| GameError::UnknownPage(id.into()))?;
+        Ok(Self::new_with_page(id, page))
+    }
+
+    /// Navigate to a registered page by id, as if through [`crate::Action::Next`].
+    pub fn jump(&mut self, id: &str) -> Result<(), GameError> {
+        let page = Registry::<C>::lookup(id).ok_or_else(|| GameError::UnknownPage(id.into()))?;
+        self.handle_action(crate::Action::Next(PageHandle::new(id.into(), page)))
+    }
+
+    /// [`Game::restore`], resolving pages through the [`Registry`].
+    pub fn restore_registered(save: GameSave<C>) -> Result<Self, GameError> {
+        Self::restore(save, |id| Registry::<C>::lookup(id))
+    }
+}
+
+// ----------------------- BOILERPLATE ---------------------------------------------------
+
+impl<C: GameContext> Default for Registry<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
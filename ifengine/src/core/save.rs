@@ -0,0 +1,118 @@
+use crate::core::{GameContext, GameInner, GameTags, Page, PageHandle, PageId, PageStack};
+use crate::{Game, GameError};
+
+/// A snapshot of a running [`Game`], including its page history.
+///
+/// [`GameInner`] skips its [`PageStack`] during serialization, since [`PageHandle`]s wrap function pointers.
+/// A save instead records each page by its resolved [`PageId`], preserving the tunnel frames, and rebinds them to pages on [`Game::restore`].
+///
+/// # Example
+/// ```rust,ignore
+/// let save = game.save()?;
+/// let json = serde_json::to_string(&save)?;
+///
+/// let save: GameSave<State> = serde_json::from_str(&json)?;
+/// let game = Game::restore(save, |id| match &**id {
+///     "story::chap1::p1" => Some(chap1::p1 as Page<State>),
+///     _ => None,
+/// })?;
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameSave<C> {
+    pub inner: GameInner,
+    /// One list of page ids per tunnel frame, oldest first.
+    pub pages: Vec<Vec<PageId>>,
+    pub context: C,
+    pub tags: GameTags,
+}
+
+impl<C: GameContext> Game<C> {
+    /// Capture the current game, including its page stack.
+    ///
+    /// If an action has queued a page which hasn't been viewed yet, its id is not known.
+    /// It is then looked up in the [`crate::core::Registry`] by its function, without calling the page.
+    /// Fails with [`GameError::PendingPage`] if the queued page isn't registered, i.e. if it is a [`crate::core::PageObject`] or the `macros` feature is off,
+    /// and with [`GameError::ParamsNotSaved`] if it was queued with [params](PageHandle::with_params).
+    pub fn save(&self) -> Result<GameSave<C>, GameError> {
+        let mut pages = self.pages.ids();
+        if let Some(pending) = pages
+            .last_mut()
+            .and_then(|frame| frame.last_mut())
+            .filter(|id| id.is_empty())
+        {
+            *pending = self.queued_id()?;
+        }
+
+        Ok(GameSave {
+            inner: self.inner.clone(),
+            pages,
+            context: self.context.clone(),
+            tags: self.tags.clone(),
+        })
+    }
+
+    /// The registered id of the page queued by an action, see [`Game::save`].
+    fn queued_id(&self) -> Result<PageId, GameError> {
+        #[cfg(feature = "macros")]
+        if let Some(page) = self.pages.current()
+            && let Some(id) = page.page().and_then(crate::core::Registry::<C>::id_of)
+        {
+            return match page.params {
+                Some(_) => Err(GameError::ParamsNotSaved(id.into())),
+                None => Ok(id.into()),
+            };
+        }
+        Err(GameError::PendingPage)
+    }
+
+    /// Rebuild a game from a [`GameSave`].
+    ///
+    /// `resolve` maps each saved [`PageId`] back to its [`Page`].
+    /// Fails with [`GameError::UnknownPage`] if an id can no longer be resolved, i.e. if the page was removed from the story.
+    ///
+    /// [Params](PageHandle::with_params) are not saved, so a save which includes a page called with them fails with [`GameError::ParamsNotSaved`].
+    /// [Hooks](crate::core::Hook) and the [context hash](Game::with_context_hash) are not saved either, and must be added again.
+    pub fn restore(
+        save: GameSave<C>,
+        mut resolve: impl FnMut(&PageId) -> Option<Page<C>>,
+    ) -> Result<Self, GameError> {
+        let GameSave {
+            mut inner,
+            pages,
+            context,
+            tags,
+        } = save;
+
+        let frames = pages
+            .into_iter()
+            .map(|frame| {
+                frame
+                    .into_iter()
+                    .map(|id| {
+                        if id.has_params() {
+                            return Err(GameError::ParamsNotSaved(id));
+                        }
+                        match resolve(&id) {
+                            Some(page) => Ok(PageHandle::new(id, page)),
+                            None => Err(GameError::UnknownPage(id)),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        inner.pages = PageStack::from_frames(frames);
+        inner.entered = false; // the hooks enter the page the game was saved on
+
+        Ok(Self {
+            inner,
+            context,
+            tags,
+            simulating: false,
+            history: Default::default(),
+            hooks: Default::default(),
+            context_hash: None,
+        })
+    }
+}
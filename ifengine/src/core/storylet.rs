@@ -0,0 +1,172 @@
+use std::any::Any;
+
+use crate::Game;
+use crate::core::{GameContext, GameTags, Page, PageErased, PageHandle, Response};
+
+/// Decides whether a [`Storylet`] can be drawn.
+pub type Condition<C> = fn(&C, &GameTags) -> bool;
+
+/// A [page](Page) which can be drawn from a [`Deck`] while its condition holds.
+#[derive(Debug, Clone)]
+pub struct Storylet<C> {
+    pub page: PageHandle,
+    pub condition: Condition<C>,
+    /// Only the eligible storylets with the highest priority are drawn from.
+    pub priority: i32,
+    /// Relative likelihood of being drawn among storylets of equal priority.
+    pub weight: u32,
+}
+
+impl<C: GameContext> Storylet<C> {
+    pub fn new(page: Page<C>) -> Self {
+        Self::new_handle(PageHandle::new("".into(), page))
+    }
+
+    pub fn new_handle(page: PageHandle) -> Self {
+        Self {
+            page,
+            condition: |_, _| true,
+            priority: 0,
+            weight: 1,
+        }
+    }
+
+    pub fn with_condition(mut self, condition: Condition<C>) -> Self {
+        self.condition = condition;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn eligible(&self, game: &Game<C>) -> bool {
+        self.weight > 0 && (self.condition)(&game.context, &game.tags)
+    }
+}
+
+/// A set of [`Storylet`]s, which switches to one of them when called as a page.
+///
+/// Among the eligible storylets, those with the highest priority are kept, and one is picked by weight.
+/// The pick is derived from the [game seed](Game::with_seed) and the number of pages viewed, so it is reproducible.
+/// Without the `rand` feature, the heaviest (and then first) storylet is picked.
+/// The [simulation](Game::simulate) branches into every storylet which could have been picked.
+/// If none are eligible, the deck switches to its fallback, or ends the game.
+///
+/// # Example
+/// ```rust,ignore
+/// fn road() -> Deck<State> {
+///     Deck::new()
+///         .with(Storylet::new(chap2::bandits).with_condition(|s, _| s.rations > 3))
+///         .with(Storylet::new(chap2::storm).with_weight(2))
+///         .with(
+///             Storylet::new(chap2::arrive)
+///                 .with_condition(|s, _| s.miles >= 100)
+///                 .with_priority(1),
+///         )
+///         .with_fallback(chap2::quiet_day)
+/// }
+///
+/// #[ifview]
+/// pub fn travel(s: &mut State) {
+///     s.miles += 10;
+///     DRAW!(road())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Deck<C> {
+    pub storylets: Vec<Storylet<C>>,
+    pub fallback: Option<PageHandle>,
+}
+
+impl<C: GameContext> Deck<C> {
+    pub fn new() -> Self {
+        Self {
+            storylets: Vec::new(),
+            fallback: None,
+        }
+    }
+
+    pub fn with(mut self, storylet: Storylet<C>) -> Self {
+        self.storylets.push(storylet);
+        self
+    }
+
+    pub fn with_fallback(mut self, page: Page<C>) -> Self {
+        self.fallback = Some(PageHandle::new("".into(), page));
+        self
+    }
+
+    /// The storylets which could currently be drawn.
+    pub fn eligible(&self, game: &Game<C>) -> Vec<&Storylet<C>> {
+        let eligible: Vec<_> = self.storylets.iter().filter(|s| s.eligible(game)).collect();
+
+        let Some(top) = eligible.iter().map(|s| s.priority).max() else {
+            return eligible;
+        };
+
+        eligible.into_iter().filter(|s| s.priority == top).collect()
+    }
+
+    /// Pick the next storylet.
+    pub fn draw(&self, game: &Game<C>) -> Option<&Storylet<C>> {
+        let eligible = self.eligible(game);
+
+        #[cfg(feature = "rand")]
+        {
+            use crate::utils::{mix_seed, pick_weighted};
+
+            let seed = mix_seed(game.seed(), game.iterations() as u64);
+            let weights: Vec<_> = eligible.iter().map(|s| s.weight).collect();
+            pick_weighted(seed, &weights).map(|i| eligible[i])
+        }
+
+        #[cfg(not(feature = "rand"))]
+        {
+            eligible
+                .into_iter()
+                .rev() // max_by_key returns the last maximum
+                .max_by_key(|s| s.weight)
+        }
+    }
+}
+
+impl<C: GameContext> PageErased for Deck<C> {
+    fn call(&self, game: &mut dyn Any) -> Response {
+        let game = game.downcast_mut::<Game<C>>().expect("Game type mismatch");
+
+        match self.draw(game) {
+            Some(storylet) => {
+                if game.simulating() {
+                    // the simulation explores the storylets which could have been drawn instead
+                    let others = self
+                        .eligible(game)
+                        .into_iter()
+                        .filter(|s| !std::ptr::eq(*s, storylet))
+                        .map(|s| s.page.clone())
+                        .collect::<Vec<_>>();
+                    game.inner.draws.extend(others);
+                }
+                Response::Switch(storylet.page.clone())
+            }
+            None => match &self.fallback {
+                Some(page) => Response::Switch(page.clone()),
+                None => Response::End,
+            },
+        }
+    }
+}
+
+// ----------------------- BOILERPLATE ---------------------------------------------------
+
+impl<C: GameContext> Default for Deck<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
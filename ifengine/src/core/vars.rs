@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A value in the [`Vars`] store.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Var {
+    Int(i64),
+    Bool(bool),
+    Text(String),
+}
+
+impl Var {
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Var::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Var::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Var::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Game-wide variables, independent of any page.
+///
+/// Unlike element state, which is stored per page in [`crate::core::game_state::GameState`], these can be read and written from every page (see [`crate::elements::var`]), and are recorded by the [simulation](crate::Game::simulate) at each ending.
+///
+/// # Example
+/// ```rust,ignore
+/// #[ifview]
+/// pub fn gate(s: &mut State) {
+///     if var!("has_key").and_then(Var::as_bool) == Some(true) {
+///         p!(link!("Unlock the gate", courtyard));
+///     } else {
+///         p!(s!("Take the key").with_action(Action::ToggleVar("has_key".into())));
+///     }
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vars(BTreeMap<String, Var>);
+
+impl Vars {
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Var> {
+        self.0.get(name)
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<Var>) -> Option<Var> {
+        self.0.insert(name.into(), value.into())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Var> {
+        self.0.remove(name)
+    }
+
+    /// Add `by` to an integer variable, saturating at the bounds of `i64`.
+    /// Initializes the variable to 0 if it does not exist or is not an integer.
+    pub fn inc(&mut self, name: impl Into<String>, by: i64) {
+        let var = self.0.entry(name.into()).or_insert(Var::Int(0));
+        let current = var.as_int().unwrap_or_default();
+        *var = Var::Int(current.saturating_add(by));
+    }
+
+    /// Flip a boolean variable.
+    /// Initializes the variable to true if it does not exist or is not a boolean.
+    pub fn toggle(&mut self, name: impl Into<String>) {
+        let var = self.0.entry(name.into()).or_insert(Var::Bool(false));
+        let current = var.as_bool().unwrap_or_default();
+        *var = Var::Bool(!current);
+    }
+}
+
+// ----------------------- BOILERPLATE ---------------------------------------------------
+
+impl From<i64> for Var {
+    fn from(value: i64) -> Self {
+        Var::Int(value)
+    }
+}
+
+impl From<i32> for Var {
+    fn from(value: i32) -> Self {
+        Var::Int(value.into())
+    }
+}
+
+impl From<bool> for Var {
+    fn from(value: bool) -> Self {
+        Var::Bool(value)
+    }
+}
+
+impl From<String> for Var {
+    fn from(value: String) -> Self {
+        Var::Text(value)
+    }
+}
+
+impl From<&str> for Var {
+    fn from(value: &str) -> Self {
+        Var::Text(value.to_string())
+    }
+}
+
+impl fmt::Display for Var {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Var::Int(n) => write!(f, "{n}"),
+            Var::Bool(b) => write!(f, "{b}"),
+            Var::Text(s) => write!(f, "{s:?}"),
+        }
+    }
+}
+
+impl fmt::Display for Vars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::ops::Deref for Vars {
+    type Target = BTreeMap<String, Var>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -69,6 +69,7 @@ mod macros_enabled {
     ///
     /// - `$e`: The text to display.
     /// - `$f`: Optional path to a page or function for the link action.
+    /// - `$p`: Optional [params](crate::core::PageHandle::with_params) for the page. Several are passed as a tuple.
     ///
     /// # Examples
     ///
@@ -77,9 +78,18 @@ mod macros_enabled {
     ///
     /// let link1 = link!("Click me", MyPage);
     /// let link2 = link!("Just a link");
+    /// let link3 = link!("Inspect", item_page, ItemId::Lamp);
     /// ```
     #[macro_export]
     macro_rules! link {
+        ($e:expr, $f:path, $($p:expr),+ $(,)?) => {
+            $crate::view::Span::from($e)
+                .as_link()
+                .with_action($crate::Action::Next(
+                    $crate::core::PageHandle::new(stringify!($f).into(), $f)
+                        .with_params(($($p),+)),
+                ))
+        };
         ($e:expr, $f:path) => {
             $crate::view::Span::from($e)
                 .as_link()
@@ -97,9 +107,18 @@ mod macros_enabled {
     ///
     /// - `$e`: Display text.
     /// - `$f`: Optional target page to tunnel to.
+    /// - `$p`: Optional [params](crate::core::PageHandle::with_params) for the page.
     /// - No parameters: produces an exit link.
     #[macro_export]
     macro_rules! tun {
+        ($e:expr, $f:path, $($p:expr),+ $(,)?) => {
+            $crate::view::Span::from($e)
+                .as_link()
+                .with_action($crate::Action::Tunnel(
+                    $crate::core::PageHandle::new(stringify!($f).into(), $f)
+                        .with_params(($($p),+)),
+                ))
+        };
         ($e:expr, $f:path) => {
             $crate::view::Span::from($e)
                 .as_link()
@@ -122,10 +141,16 @@ mod macros_enabled {
     /// This returns `!`, exiting the current function.
     ///
     /// - `$f:path`: Switch to the given page.
+    /// - `$p`: Optional [params](crate::core::PageHandle::with_params) for the page.
     ///
     /// Note: Do not rely on these in closures!
     #[macro_export]
     macro_rules! LINK {
+        ($f:path, $($p:expr),+ $(,)?) => {
+            return $crate::core::Response::Switch(
+                $crate::core::PageHandle::new(stringify!($f).into(), $f).with_params(($($p),+)),
+            );
+        };
         ($f:path) => {
             return $crate::core::Response::Switch($crate::core::PageHandle::new(
                 stringify!($f).into(),
@@ -153,11 +178,17 @@ mod macros_enabled {
     /// This returns `!`, exiting the current function.
     ///
     /// - `$f:path`: Enter a tunnel to the specified page.
+    /// - `$p`: Optional [params](crate::core::PageHandle::with_params) for the page.
     /// - No arguments: exit current tunnel.
     #[macro_export]
     macro_rules! TUN {
+        ($f:path, $($p:expr),+ $(,)?) => {
+            return $crate::core::Response::Tunnel(
+                $crate::core::PageHandle::new(stringify!($f).into(), $f).with_params(($($p),+)),
+            );
+        };
         ($f:path) => {
-            return $crate::core::Response::EnterTunnel($crate::core::PageHandle::new(
+            return $crate::core::Response::Tunnel($crate::core::PageHandle::new(
                 stringify!($f).into(),
                 $f,
             ));
@@ -167,6 +198,81 @@ mod macros_enabled {
         };
     }
 
+    /// Immediately exit the current tunnel.
+    ///
+    /// This returns `!`, exiting the current function.
+    ///
+    /// - `$v:expr`: A value for the page which entered the tunnel, read with [`tunnel_result`](crate::elements::tunnel_result).
+    /// - No arguments: exit without a value, same as `TUN!()`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// #[ifview]
+    /// pub fn dice(s: &mut State) {
+    ///     if s.rations > 0 {
+    ///         EXIT!(s.rations % 6 + 1)
+    ///     }
+    ///     p!(tun!("You have nothing to bet."));
+    /// }
+    ///
+    /// #[ifview]
+    /// pub fn tavern(s: &mut State) {
+    ///     if let Some(n) = tunnel_result!(usize) {
+    ///         p!(format!("You rolled a {n}."));
+    ///     } else {
+    ///         p!(tun!("Play dice", dice));
+    ///     }
+    /// }
+    /// ```
+    #[macro_export]
+    macro_rules! EXIT {
+        ($v:expr) => {
+            return $crate::core::Response::ExitWith(std::sync::Arc::new($v));
+        };
+        () => {
+            return $crate::core::Response::Exit;
+        };
+    }
+
+    /// Create a link [`Span`] which draws from a [`Deck`](crate::core::Deck).
+    ///
+    /// - `$e`: The text to display.
+    /// - `$deck`: An expression evaluating to a `Deck<C>`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// p!(draw!("Set out", road()));
+    /// ```
+    #[macro_export]
+    macro_rules! draw {
+        ($e:expr, $deck:expr) => {
+            $crate::view::Span::from($e)
+                .as_link()
+                .with_action($crate::Action::Next(
+                    $crate::core::PageHandle::new_erased::<$crate::core::Deck<_>>(
+                        stringify!($deck).into(),
+                        $deck,
+                    ),
+                ))
+        };
+    }
+
+    /// Immediately switch to a [`Storylet`](crate::core::Storylet) drawn from a [`Deck`](crate::core::Deck).
+    ///
+    /// This returns `!`, exiting the current function.
+    ///
+    /// - `$deck`: An expression evaluating to a `Deck<C>`.
+    #[macro_export]
+    macro_rules! DRAW {
+        ($deck:expr) => {
+            return $crate::core::Response::Switch($crate::core::PageHandle::new_erased::<
+                $crate::core::Deck<_>,
+            >(stringify!($deck).into(), $deck));
+        };
+    }
+
     /// Immediately return a [`Response::End`].
     ///
     /// This returns `!`, exiting the current function.
@@ -222,6 +328,8 @@ pub enum ChoiceVariant {
     Hidden,
     /// Always shown
     Always(Line),
+    /// Shown, but cannot be selected. The second field is the reason, displayed by the frontend.
+    Disabled(Line, String),
 }
 
 // ----------- BOILERPLATE ------------------------------
@@ -243,17 +351,23 @@ impl<T: Into<Line>> From<Option<T>> for ChoiceVariant {
 }
 
 impl ChoiceVariant {
-    pub fn as_line(self, seen: bool) -> Option<Line> {
+    pub fn disabled(line: impl Into<Line>, reason: impl Into<String>) -> Self {
+        ChoiceVariant::Disabled(line.into(), reason.into())
+    }
+
+    /// The line to display, along with the reason if it is disabled.
+    pub fn as_line(self, seen: bool) -> Option<(Line, Option<String>)> {
         match self {
             ChoiceVariant::Hidden => None,
             ChoiceVariant::Once(l) => {
                 if seen {
                     None
                 } else {
-                    Some(l)
+                    Some((l, None))
                 }
             }
-            ChoiceVariant::Always(l) => Some(l),
+            ChoiceVariant::Always(l) => Some((l, None)),
+            ChoiceVariant::Disabled(l, reason) => Some((l, Some(reason))),
         }
     }
 }
@@ -1,17 +1,75 @@
 use thiserror::Error;
 
+use crate::core::PageId;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// Errors produced while running a [`crate::Game`].
+///
+/// The [`Display`](std::fmt::Display) output is worded to be shown to players.
 #[derive(Debug, Error, std::hash::Hash, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GameError {
-    #[error("NoStack")]
-    NoStack,
-    #[error("NoPage")]
-    NoPage,
-    #[error("GameEnd")]
+    #[error("The story has lost its place{}: there is no page stack.", at(.page))]
+    NoStack {
+        /// The page being added or left, empty if unknown
+        page: PageId,
+    },
+    #[error("The story has lost its place{}: there is no page to show.", at(.page))]
+    NoPage {
+        /// The last page shown or left, empty if unknown
+        page: PageId,
+        /// The number of stack frames, see [`crate::core::PageStack::frames`]
+        frames: usize,
+    },
+    #[error("The End.")]
     End,
+    /// No page is registered under this id, or it was removed from the story since a save was made.
+    #[error("There is no page called {0}.")]
+    UnknownPage(PageId),
+    /// A page taking params was called without them, or with params of another type.
+    #[error("{page} was opened without the {expected} it needs.")]
+    MissingParams { page: PageId, expected: String },
+    /// A page queued by an action could not be saved, as it isn't registered, see [`crate::Game::save`].
+    #[error("The game can't be saved until the next page is shown.")]
+    PendingPage,
+    /// A page was woven into itself, directly or through its threads, see [`crate::elements::weave`].
+    #[error("The story is tangled: {0} is woven into itself.")]
+    WovenIntoItself(PageId),
+    /// A thread responded with something other than a view, i.e. through [`crate::BACK`] or [`crate::END`], see [`crate::elements::weave`].
+    #[error("{thread} can't leave {page}: it is woven into it.")]
+    ThreadLeftPage {
+        /// The thread, as named in `weave!`
+        thread: PageId,
+        page: PageId,
+    },
+    /// A save included a page called with params, which are not saved.
+    #[error("This save can't be restored: {0} was opened with details which are not saved.")]
+    ParamsNotSaved(PageId),
+    /// An [`crate::Action::Back`] or [`crate::core::Response::Back`] went further than the history of the current tunnel.
+    #[error("Cannot go back {n} page(s) from {page}: only {history} in history.")]
+    BackBeyondHistory {
+        page: PageId,
+        /// The number of pages in the current stack frame
+        history: usize,
+        n: usize,
+    },
+    /// An [`crate::Action::Exit`] or [`crate::core::Response::Exit`] outside of any tunnel.
+    #[error("Cannot leave {page}: it was not entered through a tunnel.")]
+    TunnelExitWithoutTunnel {
+        page: PageId,
+        /// The number of pages in the current stack frame
+        history: usize,
+    },
+}
+
+fn at(page: &PageId) -> String {
+    if page.is_empty() {
+        String::new()
+    } else {
+        format!(" at {page}")
+    }
 }
 
 #[derive(Debug, Error, std::hash::Hash, PartialEq, Eq, Clone)]
@@ -26,6 +84,19 @@ pub enum SimEnd {
     #[error("⟨Exit⟩")]
     TunnelExit,
 
+    /// A return to an equivalent state earlier on the same path, at the given page.
+    #[error("↻ {0}")]
+    Cycle(PageId),
+
+    /// A page panicked when called.
+    #[error("⟨Panic in {page}: {message}⟩")]
+    Panic {
+        page: PageId,
+        message: String,
+        /// The pages viewed on the way to the panicking page
+        path: Vec<PageId>,
+    },
+
     #[error("⟨{0}⟩")]
     Custom(String),
 }
@@ -15,6 +15,13 @@ mod errors;
 #[cfg(feature = "macros")]
 pub use ifengine_macros::ifview;
 
+// Used by macro expansions
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub use inventory;
+}
+
 pub mod elements;
 pub mod run;
 pub mod utils;
@@ -0,0 +1,63 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use crate::Game;
+use crate::core::GameContext;
+
+/// A hash of a game's context, see [`Game::with_context_hash`].
+pub type ContextHash<C> = fn(&C) -> u64;
+
+impl<C: GameContext> Game<C> {
+    /// Set the hash of the context used by [`Game::fingerprint`].
+    ///
+    /// By default, the [`Debug`] output of the context is hashed. A custom hash can be cheaper, or ignore fields which don't affect the story,
+    /// letting the [simulation](Game::simulate) merge more states.
+    pub fn with_context_hash(mut self, hash: ContextHash<C>) -> Self {
+        self.context_hash = Some(hash);
+        self
+    }
+
+    /// Identifies the state of the game, such that two games with the same fingerprint continue identically.
+    ///
+    /// Covers the element state, variables, tags, page stack, clock, whether the page is [fresh](crate::core::GameInner::fresh) and the pending tunnel result, along with the context (see [`Game::with_context_hash`]).
+    ///
+    /// The number of [iterations](crate::core::GameInner::iterations) is left out, as it would tell apart every revisit. Pages which depend on it
+    /// may then continue differently from states with the same fingerprint. [`crate::core::Deck`] draws, which are seeded by it, are not affected,
+    /// as the simulation branches into every storylet which could be drawn.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.state_fingerprint().hash(&mut hasher);
+        self.inner.pages.ids().hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// Like [`Game::fingerprint`], but only covering the current page instead of the whole page stack.
+    pub(crate) fn state_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.inner.pages.current().map(|p| p.id).hash(&mut hasher);
+        self.inner.state.hash(&mut hasher);
+        self.inner.vars.hash(&mut hasher);
+        self.inner.clock.hash(&mut hasher);
+        self.inner.fresh.hash(&mut hasher);
+        // the value of a tunnel is opaque, so it is told apart by identity
+        self.inner
+            .tunnel_result
+            .as_ref()
+            .map(|(caller, value)| (caller, Arc::as_ptr(value).cast::<()>()))
+            .hash(&mut hasher);
+
+        let mut tags: Vec<_> = self.tags.iter().map(|t| &*t.0).collect();
+        tags.sort_unstable();
+        tags.hash(&mut hasher);
+
+        match self.context_hash {
+            Some(hash) => hash(&self.context).hash(&mut hasher),
+            None => format!("{:?}", self.context).hash(&mut hasher),
+        }
+
+        hasher.finish()
+    }
+}
@@ -3,25 +3,34 @@ use std::borrow::Cow;
 use crate::{
     Action, Game, GameError, SimEnd, View,
     core::{GameContext, PageHandle, PageId, PageStack, game_state::PageKey},
-    view::{Line, Object, Span},
+    view::{Line, Object, Span, Timer},
 };
 
 #[derive(Debug, Clone, Copy)]
 pub enum Interactable<'a> {
-    Choice(&'a PageKey, &'a Vec<(u8, Line)>, u8), // parent, index of the choice
-    Span(&'a Object, &'a Span),                   // parent, the span
+    Choice(
+        &'a PageId,
+        &'a PageKey,
+        &'a Vec<(u16, Line, Option<String>)>,
+        u16,
+    ), // owner, key, parent, index of the choice
+    Span(&'a Object, &'a Span),              // parent, the span
+    Input(&'a PageId, &'a PageKey, &'a str), // owner, key, the submitted value
+    Timer(&'a Timer),                        // the timer, as if it had run out
 }
 
 impl<'a> Interactable<'a> {
     pub fn content(&self) -> Cow<'a, str> {
         match self {
-            Interactable::Choice(_, lines, idx) => lines
+            Interactable::Choice(_, _, lines, idx) => lines
                 .iter()
-                .find_map(|(i, x)| if i == idx { Some(x) } else { None })
+                .find_map(|(i, x, _)| if i == idx { Some(x) } else { None })
                 .unwrap()
                 .content()
                 .into(),
             Interactable::Span(_, s) => Cow::Borrowed(&s.content),
+            Interactable::Input(_, _, value) => Cow::Borrowed(value),
+            Interactable::Timer(timer) => Cow::Owned(format!("timeout after {:?}", timer.deadline)),
         }
     }
 }
@@ -33,13 +42,18 @@ impl View {
     ///    Interactable::Span(parent, span…),
     ///    Interactable::Span(parent, span…),
     ///    ...]
+    /// Input objects expand to one [`Interactable::Input`] for each of their sample values.
+    /// Timer objects expand to one [`Interactable::Timer`], so the simulator treats timing out as one more branch.
+    /// Both carry their [owner](View::owner), under which their state is stored.
     ///
     /// Choices which contain an interactable element are ignored!
+    /// Disabled choices are skipped entirely, see [`View::disabled_choices`].
     pub fn interactables(&self) -> Vec<Vec<Interactable<'_>>> {
         let mut out = Vec::new();
 
-        for obj in &self.inner {
+        for (index, obj) in self.inner.iter().enumerate() {
             let mut bucket = Vec::new();
+            let owner = self.owner(index);
 
             match obj {
                 Object::Text(line, _)
@@ -60,7 +74,11 @@ impl View {
                 }
 
                 Object::Choice(key, choices) => {
-                    for (i, line) in choices {
+                    for (i, line, reason) in choices {
+                        if reason.is_some() {
+                            continue;
+                        }
+
                         let ignore = {
                             if true {
                                 line.spans.iter().any(|span| span.action.is_some())
@@ -72,7 +90,7 @@ impl View {
                         };
 
                         if !ignore {
-                            bucket.push(Interactable::Choice(&key, &choices, *i));
+                            bucket.push(Interactable::Choice(owner, key, choices, *i));
                         }
 
                         for span in &line.spans {
@@ -83,6 +101,16 @@ impl View {
                     }
                 }
 
+                Object::Input(key, input) => {
+                    for value in input.sim_values() {
+                        bucket.push(Interactable::Input(owner, key, value));
+                    }
+                }
+
+                Object::Timer(timer) => {
+                    bucket.push(Interactable::Timer(timer));
+                }
+
                 Object::Image(_) | Object::Break | Object::Empty(_) | Object::Custom(_) => {
                     // no interactables
                 }
@@ -112,19 +140,40 @@ impl View {
             })
             .collect()
     }
+
+    /// All entries of the choices in the view, disabled or not.
+    pub fn choice_entries(&self) -> impl Iterator<Item = &(u16, Line, Option<String>)> {
+        self.iter()
+            .filter_map(|obj| match obj {
+                Object::Choice(_, choices) => Some(choices),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    /// Choice entries which are shown but cannot be selected, along with their reason.
+    pub fn disabled_choices(&self) -> Vec<(&Line, &str)> {
+        self.choice_entries()
+            .filter_map(|(_, line, reason)| reason.as_deref().map(|r| (line, r)))
+            .collect()
+    }
 }
 
 impl<C: GameContext> Game<C> {
-    pub fn interact(&mut self, e: Interactable<'_>, pageid: &PageId) -> Result<(), GameError> {
+    pub fn interact(&mut self, e: Interactable<'_>) -> Result<(), GameError> {
         match e {
-            Interactable::Choice(key, _, index) => {
-                self.handle_choice((pageid.clone(), *key), index);
+            Interactable::Choice(owner, key, _, index) => {
+                self.handle_choice((owner.clone(), *key), index);
                 Ok(())
             }
             Interactable::Span(_, s) => {
                 let action = s.action.as_ref().unwrap();
                 self.handle_action(action.clone()).map(|_| {})
             }
+            Interactable::Input(owner, key, value) => {
+                self.handle_action(Action::SetText((owner.clone(), *key), value.to_string()))
+            }
+            Interactable::Timer(timer) => self.fire_timer(timer),
         }
     }
 
@@ -133,7 +182,7 @@ impl<C: GameContext> Game<C> {
             .into_iter()
             .map(|e| {
                 let mut g = self.clone();
-                g.interact(e, &view.pageid).map(|_| g)
+                g.interact(e).map(|_| g)
             })
             .collect()
     }
@@ -7,3 +7,9 @@ pub use interact::*;
 
 mod sim;
 pub use sim::*;
+
+mod sim_config;
+pub use sim_config::*;
+
+mod fingerprint;
+pub use fingerprint::*;
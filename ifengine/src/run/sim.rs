@@ -1,31 +1,168 @@
 use std::{
-    collections::{HashMap, HashSet},
-    thread::current,
+    cell::Cell,
+    collections::{BTreeSet, HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+    panic::{AssertUnwindSafe, PanicHookInfo, UnwindSafe, catch_unwind},
+    sync::{
+        Arc, Condvar, Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
 };
 
 use iddqd::{IdHashMap, id_hash_map::Entry};
 
 use crate::{
     Action, Game, GameError, SimEnd, View,
-    core::{GameContext, GameTags, PageHandle, PageId, PageStack, Response, game_state::GameState},
+    core::{
+        GameContext, GameTags, PageHandle, PageId, PageStack, Response, Vars, game_state::GameState,
+    },
     utils::_dbg,
-    view::Object,
+    view::{Object, TagKind},
 };
 
-use super::Interactable;
+use super::{Budget, Interactable, SimulationConfig, SimulationStatus, TruncationReason};
 
 impl<C: GameContext> Game<C> {
-    /// The user must ensure that all cycles must be modelled by tunnels. We guarantee to never visit the same tunnel from the same location twice, but the presence of other loops will result in failure to halt. Although certain types of elements generated from proc_macros
+    /// Returning to an equivalent state on the current path, (i.e. with the same [state](Game::fingerprint), and a page stack which extends the earlier one), is recorded as a [`SimEnd::Cycle`] instead of being explored again.
+    /// Loops which keep changing the state, such as counters, are not detected, and must be bounded by the visitor or hidden with [`crate::view::Span::no_sim`].
+    /// A tunnel is entered at most once from the same caller and state, and the value it exits with (see [`crate::EXIT`]) resumes the caller, so that the branches reading it are explored.
+    /// A page which panics is recorded as a [`SimEnd::Panic`] on the page leading to it, and the rest of the story is still explored.
+    /// Such panics are printed by the panic hook like any other, unless [`SimulationConfig::quiet_panics`] is set.
     /// Panics if current game state is not a view
     /// F:
-    pub fn simulate<F>(&self, mut visitor: F) -> Simulation
+    pub fn simulate<F>(&self, visitor: F) -> Simulation
+    where
+        F: FnMut(&mut SimulationState<C>) -> bool,
+    {
+        self.simulate_with(&SimulationConfig::default(), visitor)
+    }
+
+    /// [`Game::simulate`], within the limits of a [`SimulationConfig`].
+    ///
+    /// The [status](Simulation::status) of the result records whether (and why) it was truncated.
+    pub fn simulate_with<F>(&self, config: &SimulationConfig, mut visitor: F) -> Simulation
     where
         F: FnMut(&mut SimulationState<C>) -> bool,
     {
         let mut ret = Simulation::new();
+        let budget = Budget::new(config);
+        let _quiet = config.quiet_panics.then(QuietPanics::install);
+
+        let mut tunnels_queue = vec![self.sim_start()];
+        let mut entered = HashSet::new();
+
+        while let Some((tun_id, start)) = tunnels_queue.pop() {
+            if budget.check() {
+                break;
+            }
+            if !entered.insert((tun_id.clone(), start.tunnel_entrance())) {
+                continue;
+            }
+            let records = ret.runs.entry(tun_id.clone()).or_insert(PageRecords::new());
+            let mut queue = vec![SimulationState::new(start)];
+            let mut visited: HashMap<u64, usize> = HashMap::new();
+            ret.merged += Self::simulate_impl(
+                &tun_id,
+                &mut queue,
+                records,
+                &mut tunnels_queue,
+                &mut visitor,
+                &budget,
+                &mut visited,
+            );
+        }
+
+        ret.states = budget.states();
+        ret.status = budget.into_status();
+        ret
+    }
+
+    /// [`Game::simulate_with`], spreading the runs and their unexplored states over [`SimulationConfig::threads`] worker threads.
+    ///
+    /// The records of each run are merged independently of the order the workers finish in.
+    /// As the order states are explored in varies, [`Simulation::merged`] and [`Simulation::states`] can differ from a sequential simulation, and [`SimulationConfig::max_run_states`] applies to each part of a run handed to a worker.
+    ///
+    /// This spawns threads, so it panics on targets without them, such as `wasm32-unknown-unknown`. Use [`Game::simulate_with`] there.
+    pub fn simulate_parallel<F>(&self, config: &SimulationConfig, visitor: F) -> Simulation
+    where
+        C: Send,
+        F: Fn(&mut SimulationState<C>) -> bool + Sync,
+    {
+        let budget = Budget::new(config);
+        let _quiet = config.quiet_panics.then(QuietPanics::install);
+        let threads = config
+            .threads
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+            .max(1);
+
+        let (tun_id, start) = self.sim_start();
+        let run = (tun_id, start.tunnel_entrance());
+        let jobs = SimJobs::new((run, vec![SimulationState::new(start)]));
+
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| scope.spawn(|| Self::simulate_worker(&jobs, &budget, &visitor)))
+                .collect();
+            workers
+                .into_iter()
+                .map(|w| w.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        });
+
+        // merging doesn't depend on the order, see PageRecord::merge
+        let mut ret = Simulation::new();
+        for (runs, merged) in results {
+            for (tun_id, records) in runs {
+                ret.runs
+                    .entry(tun_id)
+                    .or_insert(PageRecords::new())
+                    .merge(records);
+            }
+            ret.merged += merged;
+        }
+        ret.states = budget.states();
+        ret.status = budget.into_status();
+        ret
+    }
 
+    /// Take jobs until all are done, returning the records of each run worked on and the number of merged states.
+    fn simulate_worker<F>(
+        jobs: &SimJobs<C>,
+        budget: &Budget,
+        visitor: &F,
+    ) -> (HashMap<String, PageRecords>, usize)
+    where
+        C: Send,
+        F: Fn(&mut SimulationState<C>) -> bool + Sync,
+    {
+        let mut runs = HashMap::new();
+        let mut merged = 0;
+        let mut visitor = visitor;
+
+        while let Some(((run, mut queue), job)) = jobs.take() {
+            let mut tunnels_queue = vec![];
+            if !budget.check() {
+                let records = runs.entry(run.0.clone()).or_insert(PageRecords::new());
+                merged += Self::simulate_impl(
+                    &run.0,
+                    &mut queue,
+                    records,
+                    &mut tunnels_queue,
+                    &mut visitor,
+                    budget,
+                    &mut SharedFrontier { jobs, run: &run },
+                );
+            }
+            jobs.done(job, tunnels_queue);
+        }
+
+        (runs, merged)
+    }
+
+    /// The run id and game to start simulating from
+    fn sim_start(&self) -> (String, Self) {
         // A tunnel categorized by the function which it enters into, which does not necessarily must respond with a view
-        let tun_id = self
+        let tun_id: String = self
             .pages
             .current()
             .unwrap()
@@ -34,61 +171,107 @@ impl<C: GameContext> Game<C> {
             .next()
             .unwrap()
             .into(); // all tunnels with the same basename are grouped the same. This is because the pagehandles contained by tunnel cannot be guaranteed to have the same import style. Although this too, is still very error_prone (i.e. renames) as well as runs the risk of collisions.
-        let mut start = self.clone();
+        let mut start = self.fork();
         start.simulating = true;
 
-        let mut tunnels_queue = vec![(tun_id, start)];
-
-        while let Some((tun_id, start)) = tunnels_queue.pop() {
-            let records = ret.runs.entry(tun_id).or_insert(PageRecords::new());
-            let mut queue = vec![SimulationState::new(start)];
-            let _ = Self::simulate_impl(&mut queue, records, &mut tunnels_queue, &mut visitor);
-        }
+        (tun_id, start)
+    }
 
-        ret
+    /// Identifies where a tunnel was entered from, see [`Game::simulate`].
+    /// As the caller is returned to, entering again from an equivalent state would only repeat the run.
+    fn tunnel_entrance(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.state_fingerprint().hash(&mut hasher);
+        let ids = self.pages.ids();
+        ids.iter()
+            .rev()
+            .nth(1)
+            .and_then(|f| f.last())
+            .hash(&mut hasher);
+        hasher.finish()
     }
 
-    fn interact_sim(&mut self, e: Interactable<'_>, pageid: &PageId) -> Result<(), SimEnd> {
+    fn interact_sim(&mut self, e: Interactable<'_>) -> Result<(), SimEnd> {
         match e {
-            Interactable::Choice(key, _, index) => {
-                self.handle_choice((pageid.clone(), *key), index);
+            Interactable::Choice(owner, key, _, index) => {
+                self.handle_choice((owner.clone(), *key), index);
                 Ok(())
             }
-            Interactable::Span(_, s) => {
-                let action = s.action.as_ref().unwrap();
-                match action {
-                    Action::Tunnel(next) => {
-                        let fork_name = next.id.rsplit("::").next().unwrap().into();
+            Interactable::Input(owner, key, value) => self
+                .handle_action(Action::SetText((owner.clone(), *key), value.to_string()))
+                .map_err(|e| e.into()),
+            Interactable::Span(_, s) => self.interact_sim_action(s.action.as_ref().unwrap()),
+            Interactable::Timer(timer) => {
+                self.clock = self.clock.max(timer.deadline);
+                self.interact_sim_action(&timer.action)
+            }
+        }
+    }
 
-                        let mut next = next.clone();
-                        next.id.clear();
+    fn interact_sim_action(&mut self, action: &Action) -> Result<(), SimEnd> {
+        match action {
+            Action::Tunnel(next) => {
+                let fork_name = next.id.rsplit("::").next().unwrap().into();
 
-                        self.pages = PageStack::new_with_page(next);
-                        return Err(SimEnd::Tunnel(fork_name));
-                    }
-                    Action::Exit => Err(SimEnd::TunnelExit),
-                    _ => self
-                        .handle_action(action.clone())
-                        .map(|_| {})
-                        .map_err(|e| e.into()),
-                }
+                let mut next = next.clone();
+                next.id.clear();
+
+                // keep the caller, so that a value it exits with can be returned to it
+                self.pages.adv_stack();
+                self.tunnel_result = None;
+                self.pages.push(next)?;
+                Err(SimEnd::Tunnel(fork_name))
             }
+            Action::Exit => Err(SimEnd::TunnelExit),
+            _ => self
+                .handle_action(action.clone())
+                .map(|_| {})
+                .map_err(|e| e.into()),
         }
     }
 
+    /// Returns the number of merged states, see [`Simulation::merged`].
     fn simulate_impl<F>(
+        tun_id: &str,
         queue: &mut Vec<SimulationState<C>>,
         records: &mut PageRecords,
-        tunnels_queue: &mut Vec<(String, Self)>,
+        tunnels_queue: &mut TunnelsQueue<C>,
         visitor: &mut F,
-    ) where
+        budget: &Budget,
+        frontier: &mut impl Frontier<C>,
+    ) -> usize
+    where
         F: FnMut(&mut SimulationState<C>) -> bool,
     {
+        let mut merged = 0;
+        let mut run_states = 0;
+        let quiet = budget.config.quiet_panics;
+
         // dfs
-        while let Some(mut s) = queue.pop() {
+        loop {
+            frontier.share(queue, tunnels_queue);
+            let Some(mut s) = queue.pop() else {
+                break;
+            };
+            if budget.check() {
+                return merged;
+            }
+            if let Some(max) = budget.config.max_run_states
+                && run_states >= max
+            {
+                budget.truncate(TruncationReason::MaxRunStates(tun_id.to_string(), max));
+                return merged;
+            }
+            if let Some(max) = budget.config.max_depth
+                && s.depth > max
+            {
+                budget.truncate(TruncationReason::MaxDepth(max));
+                continue;
+            }
+
             // unimportant preflight
             let Some(mut page) = s.pages.current() else {
-                return;
+                return merged;
             };
             if page.id.is_empty() {
                 s.pages.pop(); // drop the initial page for the next rendered and (possibly same) page. In particular, it will have the fully resolved name, (while i.e. the pagehandles produced by link! in handle_action don't).
@@ -98,19 +281,56 @@ impl<C: GameContext> Game<C> {
             if !visitor(&mut s) {
                 continue; // could support custom ends here
             }
+            budget.explored(queue.len(), tunnels_queue.len());
+            run_states += 1;
 
             let v_res = loop {
-                let r = page.call(&mut s);
+                s.draws.clear(); // only those of this call are branched into
+                // a panicking page ends this branch, leaving the others to be explored
+                let r = match catch_page(
+                    quiet,
+                    AssertUnwindSafe(|| match page.call(&mut s) {
+                        Response::Weave(view, threads) => s.weave(view, threads),
+                        r => r,
+                    }),
+                ) {
+                    Ok(r) => r,
+                    Err(payload) => {
+                        let page = match (page.id.is_empty(), s.target.as_ref()) {
+                            (true, Some(target)) => target.clone(),
+                            _ => page.id.clone(),
+                        };
+                        break Err(SimEnd::Panic {
+                            page,
+                            message: panic_message(payload),
+                            path: s.path_pages(),
+                        });
+                    }
+                };
                 match r {
                     Response::View(view) => {
                         page.id = view.pageid.clone(); // id the page by the fully resolved name
                         _dbg!(&page.id);
-                        break s.pages.push(page).map(|_| view).map_err(|e| e.into()); // only rendered pages get added to history
+                        s.viewed_tunnel_result(&page.id);
+                        let id = page.id.clone();
+                        if let Err(e) = s.pages.push(page) {
+                            break Err(e.into()); // only rendered pages get added to history
+                        }
+                        s.visit(&id);
+                        break Ok(view);
                     }
                     Response::Switch(next) => {
+                        // branch into the storylets the deck could have drawn instead, as if they were linked to
+                        for mut other in std::mem::take(&mut s.draws) {
+                            let mut fork = s.clone();
+                            other.id.clear();
+                            if fork.pages.push(other).is_ok() {
+                                queue.push(fork);
+                            }
+                        }
                         page = next;
                     }
-                    Response::Back(n) => match s.pages.pop_n(n) {
+                    Response::Back(n) => match s.pages.pop_n(n, &page.id) {
                         Ok(p) => page = p,
                         Err(e) => break Err(e.into()),
                     },
@@ -118,38 +338,86 @@ impl<C: GameContext> Game<C> {
                         let mut fork = s.game.clone();
                         let fork_name = next.id.rsplit("::").next().unwrap().to_string(); // note: why compiler can't infer into_string() here
                         next.id.clear();
-                        fork.pages = PageStack::new_with_page(next);
+                        // keep the caller, so that a value it exits with can be returned to it
+                        fork.pages.adv_stack();
+                        fork.tunnel_result = None;
+                        if let Err(e) = fork.pages.push(next) {
+                            break Err(e.into());
+                        }
                         tunnels_queue.push((fork_name.clone(), fork));
                         break Err(SimEnd::Tunnel(fork_name));
                     }
-                    Response::Exit => {
+                    // resume the caller with the value, so that the branches which read it are explored
+                    Response::ExitWith(value) if s.pages.frames() > 1 => {
+                        if let Err(e) = s.pages.exit_tunnel(&page.id) {
+                            break Err(e.into());
+                        }
+                        let Some(caller) = s.pages.current() else {
+                            break Err(GameError::NoPage {
+                                page: page.id,
+                                frames: s.pages.frames(),
+                            }
+                            .into());
+                        };
+                        s.tunnel_result = Some((caller.id.clone(), value));
+                        page = caller;
+                    }
+                    Response::Exit | Response::ExitWith(_) => {
                         // we cannot fully distinguish between tunnel_exit and game_end by this response variant
                         break Err(SimEnd::TunnelExit);
                     }
                     Response::End => break Err(GameError::End.into()),
+                    Response::Error(e) => break Err(e.into()),
+                    Response::Weave(..) => unreachable!(),
                 }
             };
 
             match v_res {
                 Ok(mut v) => {
+                    s.game.hook_view(&mut v);
                     let curr_id = v.pageid.clone();
                     records.insert_view(&s, &mut v);
+
+                    // the back-edge of a cycle is recorded as an end on the page it was taken from
+                    let stack = s.pages.ids();
+                    let state = s.state_fingerprint();
+                    if s.path.iter().any(|(prev, prev_stack)| {
+                        *prev == state && stack_extends(&stack, prev_stack)
+                    }) {
+                        if let Some(last) = s.last.as_ref() {
+                            records.push_sim_end(last, SimEnd::Cycle(curr_id), &s.vars);
+                        }
+                        continue;
+                    }
+
+                    // the incoming edge is recorded, but identical states are only expanded once
+                    if !frontier.visit(s.fingerprint(), s.depth) {
+                        merged += 1;
+                        continue;
+                    }
+
                     let mut to_queue = vec![];
+                    s.path.push((state, stack));
 
                     for e in v.interactables_sim() {
                         _dbg!(&e.content());
                         let mut next = s.next(curr_id.clone());
-                        match next.interact_sim(e, &curr_id) {
+                        if let Interactable::Span(_, span) = &e
+                            && let Some(Action::Next(target)) = &span.action
+                        {
+                            next.target = Some(target.id.clone());
+                        }
+                        match next.interact_sim(e) {
                             Ok(()) => {
                                 to_queue.push(next);
                                 _dbg!(to_queue.len());
                             }
                             Err(e) => {
-                                if let SimEnd::Tunnel(fork_name) = &e {
+                                records.push_sim_end(&curr_id, e.clone(), &next.vars);
+                                if let SimEnd::Tunnel(fork_name) = e {
                                     _dbg!("tun");
-                                    tunnels_queue.push((fork_name.clone(), next.game));
+                                    tunnels_queue.push((fork_name, next.game));
                                 }
-                                records.push_sim_end(&curr_id, e.into());
                             }
                         }
                     }
@@ -157,21 +425,40 @@ impl<C: GameContext> Game<C> {
                     queue.extend(to_queue.into_iter().rev());
                 }
                 Err(e) => {
-                    if let Some(last) = s.last.as_ref() {
-                        records.push_sim_end(last, e)
+                    if matches!(e, SimEnd::GameError(GameError::End)) {
+                        s.game.hook_end();
+                    }
+                    match s.last.as_ref() {
+                        Some(last) => records.push_sim_end(last, e, &s.vars),
+                        None => {
+                            // the start has no record to hold the end otherwise
+                            let SimEnd::Panic { page: id, .. } = &e else {
+                                continue;
+                            };
+                            let id = id.clone();
+                            if !records.contains_key(&id) {
+                                records.insert_overwrite(PageRecord::new(id.clone()));
+                            }
+                            records.push_sim_end(&id, e, &s.vars)
+                        }
                     }
                 }
             };
         }
+
+        merged
     }
 }
 
-// need a way to collapse paths which lead to the same result
-
 #[derive(Debug, Clone)]
 pub struct Simulation {
     /// A history of runs, one for each starting point. Starts consist of tunnel entrances and the initial game start.
     pub runs: HashMap<String, PageRecords>,
+    /// The number of times a state was reached which had already been expanded, see [`Game::fingerprint`].
+    pub merged: usize,
+    /// The number of states explored
+    pub states: usize,
+    pub status: SimulationStatus,
 }
 
 #[derive(Debug, Clone)]
@@ -179,6 +466,12 @@ pub struct SimulationState<C> {
     pub game: Game<C>,
     pub depth: usize,
     pub last: Option<PageId>,
+    /// The state fingerprints and page stacks of the views leading here
+    path: Vec<(u64, Vec<Vec<PageId>>)>,
+    /// The (unresolved) name of the page linked to by the interaction leading here
+    target: Option<PageId>,
+    /// The achievements unlocked on the way here, see [`crate::core::Achievements::observe`]
+    pub achieved: BTreeSet<String>,
 }
 
 impl<C: Clone> SimulationState<C> {
@@ -187,6 +480,9 @@ impl<C: Clone> SimulationState<C> {
             game,
             depth: 0,
             last: None,
+            path: vec![],
+            target: None,
+            achieved: BTreeSet::new(),
         }
     }
 
@@ -194,23 +490,283 @@ impl<C: Clone> SimulationState<C> {
         let mut ret = self.clone();
         ret.depth += 1;
         ret.last = Some(curr_id);
+        ret.target = None;
         ret
     }
+
+    /// The pages viewed on the way here
+    fn path_pages(&self) -> Vec<PageId> {
+        self.path
+            .iter()
+            .filter_map(|(_, stack)| stack.last()?.last().cloned())
+            .collect()
+    }
+}
+
+thread_local! {
+    /// Whether a page is being called by a simulation with [`SimulationConfig::quiet_panics`] on this thread
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// [`catch_unwind`] around a page call, which the hook installed by [`QuietPanics`] doesn't print if `quiet`.
+fn catch_page<R>(quiet: bool, f: impl FnOnce() -> R + UnwindSafe) -> std::thread::Result<R> {
+    let was = QUIET.replace(quiet);
+    let r = catch_unwind(f);
+    QUIET.set(was);
+    r
+}
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// The number of running simulations with [`SimulationConfig::quiet_panics`], and the hook they replaced.
+static QUIET_RUNS: Mutex<(usize, Option<Arc<PanicHook>>)> = Mutex::new((0, None));
+
+/// Keeps the panics of pages off stderr while a simulation with [`SimulationConfig::quiet_panics`] runs.
+///
+/// The first such simulation replaces the panic hook with one which skips panics caught by [`catch_page`], and forwards the others to the previous hook.
+/// The last one to finish restores the previous hook.
+struct QuietPanics;
+
+impl QuietPanics {
+    fn install() -> Self {
+        let mut runs = QUIET_RUNS.lock().unwrap_or_else(PoisonError::into_inner);
+        if runs.0 == 0 {
+            let prev = Arc::new(std::panic::take_hook());
+            runs.1 = Some(prev.clone());
+            std::panic::set_hook(Box::new(move |info| {
+                if !QUIET.get() {
+                    prev(info)
+                }
+            }));
+        }
+        runs.0 += 1;
+        QuietPanics
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        let mut runs = QUIET_RUNS.lock().unwrap_or_else(PoisonError::into_inner);
+        runs.0 -= 1;
+        // the hook can't be changed while unwinding, in which case the forwarding hook stays
+        if runs.0 == 0
+            && !std::thread::panicking()
+            && let Some(prev) = runs.1.take()
+        {
+            drop(std::panic::take_hook()); // releases the forwarding hook's reference
+            if let Ok(prev) = Arc::try_unwrap(prev) {
+                std::panic::set_hook(prev);
+            }
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// The tunnels reached by a run, with the game to start them from
+type TunnelsQueue<C> = Vec<(String, Game<C>)>;
+
+/// Tracks the states explored by [`Game::simulate_impl`], and hands off work to other threads.
+trait Frontier<C> {
+    /// Record the fingerprint of a view, returning whether it should be expanded.
+    fn visit(&mut self, fingerprint: u64, depth: usize) -> bool;
+
+    /// Offered the queues before each state.
+    fn share(
+        &mut self,
+        _queue: &mut Vec<SimulationState<C>>,
+        _tunnels_queue: &mut TunnelsQueue<C>,
+    ) {
+    }
+}
+
+/// The fingerprints of the states expanded so far, with the least depth they were expanded at.
+impl<C> Frontier<C> for HashMap<u64, usize> {
+    /// A state is expanded if it was not yet expanded at this or a lesser depth.
+    fn visit(&mut self, fingerprint: u64, depth: usize) -> bool {
+        match self.entry(fingerprint) {
+            std::collections::hash_map::Entry::Occupied(mut e) => {
+                if *e.get() <= depth {
+                    return false;
+                }
+                e.insert(depth);
+            }
+            std::collections::hash_map::Entry::Vacant(e) => {
+                e.insert(depth);
+            }
+        }
+        true
+    }
+}
+
+/// The [`Frontier`] of a worker in [`Game::simulate_parallel`]
+struct SharedFrontier<'a, C> {
+    jobs: &'a SimJobs<C>,
+    run: &'a SimRun,
+}
+
+impl<C: GameContext> Frontier<C> for SharedFrontier<'_, C> {
+    fn visit(&mut self, fingerprint: u64, depth: usize) -> bool {
+        let mut visited = self.jobs.visited.lock().unwrap();
+        let visited = visited.entry(self.run.clone()).or_default();
+        Frontier::<C>::visit(visited, fingerprint, depth)
+    }
+
+    fn share(&mut self, queue: &mut Vec<SimulationState<C>>, tunnels_queue: &mut TunnelsQueue<C>) {
+        self.jobs.share(self.run, queue, tunnels_queue)
+    }
+}
+
+/// A run id and the [entrance](Game::tunnel_entrance) it was started from.
+/// Like the separate runs of [`Game::simulate_with`], each entrance has its own expanded states.
+type SimRun = (String, u64);
+
+/// A run and the states waiting to be explored in it
+type SimJob<C> = (SimRun, Vec<SimulationState<C>>);
+
+/// The work shared between the threads of [`Game::simulate_parallel`].
+struct SimJobs<C> {
+    /// The waiting jobs, and the number of jobs being worked on
+    state: Mutex<(Vec<SimJob<C>>, usize)>,
+    ready: Condvar,
+    /// The number of workers waiting for a job
+    idle: AtomicUsize,
+    /// The expanded states of each run
+    visited: Mutex<HashMap<SimRun, HashMap<u64, usize>>>,
+    /// The tunnels queued so far, see [`Game::tunnel_entrance`]
+    entered: Mutex<HashSet<SimRun>>,
+}
+
+impl<C> SimJobs<C> {
+    fn new(job: SimJob<C>) -> Self {
+        Self {
+            entered: Mutex::new(HashSet::from([job.0.clone()])),
+            state: Mutex::new((vec![job], 0)),
+            ready: Condvar::new(),
+            idle: AtomicUsize::new(0),
+            visited: Default::default(),
+        }
+    }
+
+    /// The jobs starting the tunnels which were not yet entered from an equivalent state.
+    fn tunnel_jobs(&self, tunnels_queue: TunnelsQueue<C>) -> Vec<SimJob<C>>
+    where
+        C: GameContext,
+    {
+        let mut entered = self.entered.lock().unwrap();
+        tunnels_queue
+            .into_iter()
+            .map(|(tun_id, game)| ((tun_id, game.tunnel_entrance()), game))
+            .filter(|(run, _)| entered.insert(run.clone()))
+            .map(|(run, game)| (run, vec![SimulationState::new(game)]))
+            .collect()
+    }
+
+    /// Wait for a job, returning None once there are no more jobs and none are being worked on.
+    fn take(&self) -> Option<(SimJob<C>, InFlight<'_, C>)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(job) = state.0.pop() {
+                state.1 += 1;
+                return Some((job, InFlight(self)));
+            }
+            if state.1 == 0 {
+                self.ready.notify_all();
+                return None;
+            }
+            self.idle.fetch_add(1, Ordering::Relaxed);
+            state = self.ready.wait(state).unwrap();
+            self.idle.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Finish a job, queueing the tunnels it reached.
+    fn done(&self, job: InFlight<'_, C>, tunnels_queue: TunnelsQueue<C>)
+    where
+        C: GameContext,
+    {
+        let jobs = self.tunnel_jobs(tunnels_queue);
+        self.state.lock().unwrap().0.extend(jobs);
+        drop(job);
+    }
+
+    /// Queue the reached tunnels, and hand the shallowest half of the states to idle workers.
+    fn share(
+        &self,
+        run: &SimRun,
+        queue: &mut Vec<SimulationState<C>>,
+        tunnels_queue: &mut TunnelsQueue<C>,
+    ) where
+        C: GameContext,
+    {
+        let idle = self.idle.load(Ordering::Relaxed) > 0;
+        if !idle || (tunnels_queue.is_empty() && queue.len() < 2) {
+            return;
+        }
+
+        let jobs = self.tunnel_jobs(std::mem::take(tunnels_queue));
+        let mut state = self.state.lock().unwrap();
+        state.0.extend(jobs);
+        if queue.len() >= 2 {
+            // the bottom of the stack holds the shallowest, (and likely largest) subtrees
+            let half = queue.drain(..queue.len() / 2).collect();
+            state.0.push((run.clone(), half));
+        }
+        self.ready.notify_all();
+    }
+}
+
+/// A job taken from [`SimJobs`], which is counted as being worked on until dropped.
+/// As this includes unwinding, the other workers don't wait on a job of a panicked worker.
+struct InFlight<'a, C>(&'a SimJobs<C>);
+
+impl<C> Drop for InFlight<'_, C> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.1 -= 1;
+        self.0.ready.notify_all();
+    }
+}
+
+/// Whether `stack` is `prev` with possibly more pages pushed onto its current frame.
+fn stack_extends(stack: &[Vec<PageId>], prev: &[Vec<PageId>]) -> bool {
+    match (stack.split_last(), prev.split_last()) {
+        (Some((curr, frames)), Some((prev_curr, prev_frames))) => {
+            frames == prev_frames && curr.starts_with(prev_curr)
+        }
+        _ => stack.is_empty() && prev.is_empty(),
+    }
 }
 
 // note: outgoing_tunnels not currently implemented
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PageRecord {
     pub id: PageId,
     pub ends: HashSet<SimEnd>,
-    pub tags: GameTags,
+    /// The [variables](Vars) held on reaching each end, if any were set
+    pub end_vars: HashMap<SimEnd, HashSet<Vars>>,
+    /// The tags added by the page's views.
+    /// A tag added with different kinds keeps the most persistent one, (sticky, then once, then view), regardless of the order the views were simulated in.
+    pub tags: HashMap<PageId, TagKind>,
+    /// The disabled choices shown by the page's views, with their reason
+    pub disabled: HashMap<String, String>,
+    /// The choices which were selectable in at least one of the page's views
+    pub selectable: HashSet<String>,
     pub incoming: HashSet<PageId>,
     pub min_depth: usize,
     pub outgoing_tunnels: HashSet<PageId>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PageRecords(pub IdHashMap<PageRecord>);
 
 impl PageRecord {
@@ -218,20 +774,89 @@ impl PageRecord {
         PageRecord {
             id,
             ends: Default::default(),
+            end_vars: Default::default(),
             tags: Default::default(),
+            disabled: Default::default(),
+            selectable: Default::default(),
             incoming: Default::default(),
             min_depth: usize::MAX,
             outgoing_tunnels: Default::default(),
         }
     }
 
+    /// Combine the records of the same page from different workers, see [`Game::simulate_parallel`].
+    /// Records of the same page can be merged in any order, with the same result.
+    pub fn merge(&mut self, other: PageRecord) {
+        self.ends.extend(other.ends);
+        for (end, vars) in other.end_vars {
+            self.end_vars.entry(end).or_default().extend(vars);
+        }
+        self.record_tags(other.tags);
+        for (choice, reason) in other.disabled {
+            // keep the same reason regardless of the merge order
+            match self.disabled.get_mut(&choice) {
+                Some(prev) if *prev <= reason => {}
+                Some(prev) => *prev = reason,
+                None => {
+                    self.disabled.insert(choice, reason);
+                }
+            }
+        }
+        self.selectable.extend(other.selectable);
+        self.incoming.extend(other.incoming);
+        self.min_depth = self.min_depth.min(other.min_depth);
+        self.outgoing_tunnels.extend(other.outgoing_tunnels);
+    }
+
     pub fn split(mut self) -> (Self, HashSet<PageId>) {
         let incoming = std::mem::take(&mut self.incoming);
         (self, incoming)
     }
 
+    /// Disabled choices which were never selectable in any simulated view.
+    pub fn never_selectable(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.disabled
+            .iter()
+            .filter(|(content, _)| !self.selectable.contains(*content))
+    }
+
+    fn record_tags(&mut self, tags: impl IntoIterator<Item = (PageId, TagKind)>) {
+        fn persistence(kind: &TagKind) -> u8 {
+            match kind {
+                TagKind::View => 0,
+                TagKind::Once => 1,
+                TagKind::Sticky => 2,
+            }
+        }
+
+        for (tag, kind) in tags {
+            match self.tags.get_mut(&tag) {
+                Some(prev) if persistence(prev) >= persistence(&kind) => {}
+                Some(prev) => *prev = kind,
+                None => {
+                    self.tags.insert(tag, kind);
+                }
+            }
+        }
+    }
+
+    fn record_choices(&mut self, v: &View) {
+        for (_, line, reason) in v.choice_entries() {
+            match reason {
+                Some(reason) => {
+                    self.disabled
+                        .entry(line.content())
+                        .or_insert_with(|| reason.clone());
+                }
+                None => {
+                    self.selectable.insert(line.content());
+                }
+            }
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.ends.is_empty() && self.tags.is_empty()
+        self.ends.is_empty() && self.tags.is_empty() && self.never_selectable().next().is_none()
     }
 
     pub fn compute_display_width(&self) -> usize {
@@ -242,8 +867,14 @@ impl PageRecord {
                 max = len;
             }
         }
-        for tag in &self.tags {
-            let len = tag.len();
+        for (tag, kind) in &self.tags {
+            let len = tag.len() + kind.to_string().len() + 3;
+            if len > max {
+                max = len;
+            }
+        }
+        for (choice, reason) in self.never_selectable() {
+            let len = choice.len() + reason.len() + 3;
             if len > max {
                 max = len;
             }
@@ -253,7 +884,7 @@ impl PageRecord {
 }
 
 impl PageRecords {
-    // Drains the seen tags into the record, and adds an incoming edge
+    // Drains the seen tags into the record, records its choices, and adds an incoming edge
     pub fn insert_view<C>(&mut self, s: &SimulationState<C>, v: &mut View) {
         let pageid = v.pageid.clone();
         let prev = s.last.clone();
@@ -262,7 +893,8 @@ impl PageRecords {
             Entry::Occupied(mut occ) => {
                 let mut record = occ.get_mut();
 
-                record.tags.extend(v.tags.drain(0..v.tags.len()));
+                record.record_tags(v.tags.drain(0..v.tags.len()));
+                record.record_choices(v);
                 if let Some(prev) = prev {
                     record.incoming.insert(prev);
                 }
@@ -272,7 +904,8 @@ impl PageRecords {
             Entry::Vacant(vac) => {
                 let mut record = PageRecord::new(pageid);
 
-                record.tags.extend(v.tags.drain(0..v.tags.len()));
+                record.record_tags(v.tags.drain(0..v.tags.len()));
+                record.record_choices(v);
                 if let Some(prev) = prev {
                     record.incoming.insert(prev);
                 }
@@ -283,9 +916,27 @@ impl PageRecords {
         }
     }
 
-    pub fn push_sim_end(&mut self, pageid: &PageId, e: SimEnd) {
+    pub fn push_sim_end(&mut self, pageid: &PageId, e: SimEnd, vars: &Vars) {
         if let Some(mut record) = self.0.get_mut(pageid) {
-            record.ends.insert(e.into());
+            if !vars.is_empty() {
+                record
+                    .end_vars
+                    .entry(e.clone())
+                    .or_default()
+                    .insert(vars.clone());
+            }
+            record.ends.insert(e);
+        }
+    }
+
+    pub fn merge(&mut self, other: PageRecords) {
+        for record in other.0 {
+            match self.entry(&record.id.clone()) {
+                Entry::Occupied(mut occ) => occ.get_mut().merge(record),
+                Entry::Vacant(vac) => {
+                    vac.insert(record);
+                }
+            }
         }
     }
 
@@ -333,6 +984,9 @@ impl Simulation {
     pub fn new() -> Self {
         Simulation {
             runs: Default::default(),
+            merged: 0,
+            states: 0,
+            status: SimulationStatus::Complete,
         }
     }
 }
@@ -0,0 +1,264 @@
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+use thiserror::Error;
+
+/// Limits and controls for [`crate::Game::simulate_with`].
+///
+/// All limits are unset by default. Exceeding one [truncates](SimulationStatus::Truncated) the [`super::Simulation`].
+#[derive(Clone, Default)]
+pub struct SimulationConfig {
+    /// The maximum number of states to explore, over all runs
+    pub max_states: Option<usize>,
+    /// States deeper than this are not explored
+    pub max_depth: Option<usize>,
+    /// The maximum (wall-clock) duration of the simulation.
+    /// Ignored on `wasm32`, where [`std::time::Instant`] panics, so set [`SimulationConfig::max_states`] there too.
+    pub time_budget: Option<Duration>,
+    /// The maximum number of states to explore in each run, see [`super::Simulation::runs`].
+    /// With [`crate::Game::simulate_parallel`], it applies to each part of a run handed to a worker instead.
+    pub max_run_states: Option<usize>,
+    /// Called after each explored state
+    pub progress: Option<ProgressCallback>,
+    pub cancel: Option<CancelToken>,
+    /// The number of worker threads used by [`crate::Game::simulate_parallel`], defaults to the available parallelism
+    pub threads: Option<usize>,
+    /// Keep the panics of pages, which are recorded as [`crate::SimEnd::Panic`], off stderr.
+    /// While the simulation runs, the panic hook is replaced by one which skips them and forwards other panics to the previous hook, which is restored afterwards.
+    pub quiet_panics: bool,
+}
+
+impl SimulationConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_states(mut self, max_states: usize) -> Self {
+        self.max_states = Some(max_states);
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// See [`SimulationConfig::time_budget`], which is ignored on `wasm32`.
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    pub fn with_max_run_states(mut self, max_run_states: usize) -> Self {
+        self.max_run_states = Some(max_run_states);
+        self
+    }
+
+    pub fn with_progress(
+        mut self,
+        progress: impl Fn(&SimulationProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    pub fn with_quiet_panics(mut self, quiet_panics: bool) -> Self {
+        self.quiet_panics = quiet_panics;
+        self
+    }
+}
+
+/// See [`SimulationConfig::with_progress`].
+pub type ProgressCallback = Arc<dyn Fn(&SimulationProgress) + Send + Sync>;
+
+/// Passed to the [progress callback](SimulationConfig::with_progress).
+#[derive(Debug, Clone)]
+pub struct SimulationProgress {
+    /// The number of states explored so far
+    pub states: usize,
+    /// The number of states waiting to be explored in the current run
+    pub queued: usize,
+    /// The number of runs waiting to be started
+    pub runs_queued: usize,
+}
+
+/// Stops a simulation from another thread, or from the [progress callback](SimulationConfig::with_progress).
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SimulationStatus {
+    /// Every reachable state was explored, (save for those rejected by the visitor)
+    #[default]
+    Complete,
+    Truncated(TruncationReason),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TruncationReason {
+    #[error("Exceeded the maximum of {0} states")]
+    MaxStates(usize),
+    #[error("Pruned states deeper than {0}")]
+    MaxDepth(usize),
+    #[error("Exceeded the time budget of {0:?}")]
+    TimeBudget(Duration),
+    /// A run, identified by its tunnel, exceeded [`SimulationConfig::max_run_states`]
+    #[error("Exceeded the maximum of {1} states in {0}")]
+    MaxRunStates(String, usize),
+    #[error("Cancelled")]
+    Cancelled,
+}
+
+impl TruncationReason {
+    /// Whether the whole simulation is stopped, rather than a part of it being skipped.
+    pub fn is_stop(&self) -> bool {
+        matches!(
+            self,
+            Self::MaxStates(_) | Self::TimeBudget(_) | Self::Cancelled
+        )
+    }
+}
+
+/// Tracks a simulation against its [`SimulationConfig`], shared between the workers of a [parallel simulation](crate::Game::simulate_parallel).
+pub(crate) struct Budget<'a> {
+    pub config: &'a SimulationConfig,
+    #[cfg(not(target_arch = "wasm32"))]
+    started: Option<Instant>,
+    states: AtomicUsize,
+    status: Mutex<SimulationStatus>,
+}
+
+impl<'a> Budget<'a> {
+    pub fn new(config: &'a SimulationConfig) -> Self {
+        Self {
+            config,
+            #[cfg(not(target_arch = "wasm32"))]
+            started: config.time_budget.map(|_| Instant::now()),
+            states: AtomicUsize::new(0),
+            status: Mutex::new(SimulationStatus::Complete),
+        }
+    }
+
+    /// Record the truncation, keeping the first reason, unless a later one stops the simulation.
+    pub fn truncate(&self, reason: TruncationReason) {
+        let mut status = self.status.lock().unwrap();
+        match &*status {
+            SimulationStatus::Complete => *status = SimulationStatus::Truncated(reason),
+            SimulationStatus::Truncated(prev) if !prev.is_stop() && reason.is_stop() => {
+                *status = SimulationStatus::Truncated(reason)
+            }
+            _ => {}
+        }
+    }
+
+    pub fn stopped(&self) -> bool {
+        matches!(&*self.status.lock().unwrap(), SimulationStatus::Truncated(r) if r.is_stop())
+    }
+
+    /// Check the limits on the whole simulation, returns whether to stop.
+    pub fn check(&self) -> bool {
+        if self.stopped() {
+            return true;
+        }
+        if self
+            .config
+            .cancel
+            .as_ref()
+            .is_some_and(|c| c.is_cancelled())
+        {
+            self.truncate(TruncationReason::Cancelled);
+        } else if let Some(max) = self.config.max_states
+            && self.states() >= max
+        {
+            self.truncate(TruncationReason::MaxStates(max));
+        } else if let Some(budget) = self.over_time() {
+            self.truncate(TruncationReason::TimeBudget(budget));
+        }
+        self.stopped()
+    }
+
+    /// The time budget, if it is used up.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn over_time(&self) -> Option<Duration> {
+        let (Some(budget), Some(started)) = (self.config.time_budget, self.started) else {
+            return None;
+        };
+        (started.elapsed() >= budget).then_some(budget)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn over_time(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Count an explored state, and report the progress.
+    pub fn explored(&self, queued: usize, runs_queued: usize) {
+        let states = self.states.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(progress) = &self.config.progress {
+            progress(&SimulationProgress {
+                states,
+                queued,
+                runs_queued,
+            });
+        }
+    }
+
+    pub fn states(&self) -> usize {
+        self.states.load(Ordering::Relaxed)
+    }
+
+    pub fn into_status(self) -> SimulationStatus {
+        self.status.into_inner().unwrap()
+    }
+}
+
+// ----------------------- BOILERPLATE ---------------------------------------------------
+
+impl std::fmt::Debug for SimulationConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimulationConfig")
+            .field("max_states", &self.max_states)
+            .field("max_depth", &self.max_depth)
+            .field("time_budget", &self.time_budget)
+            .field("max_run_states", &self.max_run_states)
+            .field("progress", &self.progress.is_some())
+            .field("cancel", &self.cancel)
+            .field("threads", &self.threads)
+            .field("quiet_panics", &self.quiet_panics)
+            .finish()
+    }
+}
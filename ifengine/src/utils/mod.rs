@@ -6,6 +6,9 @@ pub use text::*;
 mod mask;
 pub use mask::*;
 
+mod seed;
+pub use seed::*;
+
 macro_rules! _dbg {
     ($($t:tt)*) => {
         #[cfg(debug_assertions)]
@@ -0,0 +1,98 @@
+use std::hash::{Hash, Hasher};
+
+/// Derive a new seed from `seed` and `value` (splitmix64).
+///
+/// Used to give each page and key of a [`crate::Game`] its own stream of randomness.
+pub fn mix_seed(seed: u64, value: u64) -> u64 {
+    let mut z = seed ^ value.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// An index in `0..n` picked by `seed`, or `None` if `n` is 0.
+///
+/// Unlike the generators and sampling of `rand`, this is pinned, so a seed picks the same index on every version,
+/// and a restored save replays its random branches identically.
+pub fn pick(seed: u64, n: usize) -> Option<usize> {
+    // scale a splitmix64 step onto 0..n
+    (n > 0).then(|| ((mix_seed(seed, 0) as u128 * n as u128) >> 64) as usize)
+}
+
+/// An index into `weights` picked by `seed`, each with a chance proportional to its weight, or `None` if they sum to 0.
+///
+/// Pinned like [`pick`].
+pub fn pick_weighted(seed: u64, weights: &[u32]) -> Option<usize> {
+    let total: u64 = weights.iter().map(|w| *w as u64).sum();
+    let mut at = pick(seed, usize::try_from(total).ok()?)? as u64;
+    weights.iter().position(|w| {
+        let hit = at < *w as u64;
+        at = at.saturating_sub(*w as u64);
+        hit
+    })
+}
+
+/// A stable (FNV-1a) hash of a string, suitable for [`mix_seed`].
+pub fn hash_str(s: &str) -> u64 {
+    const_fnv1a_hash::fnv1a_hash_str_64(s)
+}
+
+/// A stable hash of any [`Hash`] value, which unlike [`std::hash::DefaultHasher`] doesn't change between builds or platforms.
+pub fn hash_value(value: &impl Hash) -> u64 {
+    let mut hasher = StableHasher(Vec::new());
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// FNV-1a of the written bytes, with integers written in little-endian and `usize` widened to 64 bits.
+struct StableHasher(Vec<u8>);
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        const_fnv1a_hash::fnv1a_hash_64(&self.0, None)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16)
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32)
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128)
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as u64)
+    }
+}
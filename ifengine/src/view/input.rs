@@ -0,0 +1,33 @@
+/// Text input type.
+/// See [`crate::view::Object`].
+#[derive(Debug, Clone, Default)]
+pub struct Input {
+    /// Hint text shown while the field is empty.
+    pub placeholder: String,
+    /// Values tried by [`crate::Game::simulate`], one branch each.
+    /// If empty, the placeholder is submitted instead.
+    pub samples: Vec<String>,
+}
+
+impl Input {
+    pub fn new(placeholder: impl Into<String>) -> Self {
+        Input {
+            placeholder: placeholder.into(),
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn with_samples<S: Into<String>>(mut self, samples: impl IntoIterator<Item = S>) -> Self {
+        self.samples = samples.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The values the simulator submits.
+    pub fn sim_values(&self) -> Vec<&str> {
+        if self.samples.is_empty() {
+            vec![self.placeholder.as_str()]
+        } else {
+            self.samples.iter().map(String::as_str).collect()
+        }
+    }
+}
@@ -144,7 +144,7 @@ impl Line {
                     spans.push(
                         Span::from_lingual(part)
                             .as_link()
-                            .with_action(Action::SetBit(key.clone(), i as u8 / 2)),
+                            .with_action(Action::SetBit(key.clone(), i as u16 / 2)),
                     );
                 } else {
                     let h: u64;
@@ -1,10 +1,16 @@
 //! The [`View`] and its constituents.
 
 mod image;
+mod input;
 mod line;
+mod timer;
 
 pub use image::*;
+pub use input::*;
 pub use line::*;
+pub use timer::*;
+
+use std::ops::Range;
 
 #[allow(unused)]
 use crate::core::{Page, PageId, game_state::PageKey};
@@ -27,11 +33,16 @@ pub enum Object {
     /// Text with a single-spaced y-margin.
     Paragraph(Line),
     /// A list of selectable texts which stores the selected index on click.
+    /// Entries carrying a reason are disabled: they are shown, but cannot be selected, and the reason should be displayed alongside (i.e. on hover).
     ///
     /// Like the paragraph variant, this includes a single-spaced y-margin
-    Choice(PageKey, Vec<(u8, Line)>),
+    Choice(PageKey, Vec<(u16, Line, Option<String>)>),
     /// See [`Image`]
     Image(Image),
+    /// A text field which stores the submitted string under its key.
+    ///
+    /// See [`Input`]
+    Input(PageKey, Input),
     /// Markdown heading
     Heading(Span, u8),
     /// Horizontal line
@@ -47,11 +58,27 @@ pub enum Object {
     Note(Line, (u8, u8)),
     /// Quote style.
     Quote(Line, RenderData),
+    /// A countdown, see [`Timer`].
+    ///
+    /// Frontends should call [`crate::Game::tick`] every frame while one is shown, and may display the remaining time.
+    Timer(Timer),
     /// Custom marker.
     /// For example, can be used signal to the frontend to play music when this object enters the screen.
     Custom(RenderData),
 }
 
+/// How a tag applies, see [`crate::elements::tag`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TagKind {
+    /// Applies to the current view only.
+    View,
+    /// Persists in [`crate::core::GameTags`] across pages, until removed.
+    Sticky,
+    /// Persists like [`TagKind::Sticky`], but is only added to the views of the visit it first fires on.
+    Once,
+}
+
 /// The view returned by a [`Page`].
 ///
 /// The job of the ui library is to process its objects (i.e. by rendering).
@@ -62,7 +89,9 @@ pub enum Object {
 pub struct View {
     pub inner: Vec<Object>,
     pub pageid: PageId,
-    pub tags: Vec<PageId>,
+    pub tags: Vec<(PageId, TagKind)>,
+    /// Ranges of objects contributed by [woven](crate::elements::weave) pages, innermost last.
+    pub threads: Vec<(Range<usize>, PageId)>,
 }
 
 impl View {
@@ -71,16 +100,63 @@ impl View {
             inner: vec![],
             pageid: name,
             tags: vec![],
+            threads: vec![],
         }
     }
 
     pub fn name(&self) -> PageId {
         self.pageid.clone()
     }
+
+    /// The page which produced the object at index `i`.
+    /// The state of [`Object::Choice`] and [`Object::Input`] is stored under this page.
+    pub fn owner(&self, i: usize) -> &PageId {
+        self.threads
+            .iter()
+            .rev()
+            .find_map(|(range, id)| range.contains(&i).then_some(id))
+            .unwrap_or(&self.pageid)
+    }
+
+    /// Insert the objects of `thread` at index `at`.
+    pub fn merge_thread(&mut self, at: usize, thread: View) {
+        let View {
+            inner,
+            pageid,
+            tags,
+            threads,
+        } = thread;
+        let len = inner.len();
+
+        for (range, _) in self.threads.iter_mut() {
+            if range.start >= at {
+                *range = range.start + len..range.end + len;
+            }
+        }
+        self.threads.push((at..at + len, pageid));
+        self.threads.extend(
+            threads
+                .into_iter()
+                .map(|(range, id)| (range.start + at..range.end + at, id)),
+        );
+
+        self.inner.splice(at..at, inner);
+        self.tags.extend(tags);
+    }
 }
 
 // --------------- BOILERPLATE ----------------
 
+impl std::fmt::Display for TagKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TagKind::View => "view",
+            TagKind::Sticky => "sticky",
+            TagKind::Once => "once",
+        })
+    }
+}
+
 impl std::ops::Deref for View {
     type Target = Vec<Object>;
     fn deref(&self) -> &Self::Target {
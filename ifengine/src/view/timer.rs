@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use crate::Action;
+
+/// An action which fires once the game clock reaches its deadline.
+/// See [`crate::view::Object::Timer`].
+///
+/// Time is only advanced by [`crate::Game::tick`], so timers stay deterministic.
+#[derive(Debug, Clone, Default)]
+pub struct Timer {
+    /// A point on the [game clock](crate::core::GameInner::clock).
+    pub deadline: Duration,
+    /// Fired when the deadline passes.
+    /// Timers with [`Action::None`] only prompt a new view, i.e. to reveal delayed text.
+    pub action: Action,
+}
+
+impl Timer {
+    pub fn new(deadline: Duration) -> Self {
+        Timer {
+            deadline,
+            action: Action::None,
+        }
+    }
+
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Time left until the deadline, given the current clock.
+    pub fn remaining(&self, clock: Duration) -> Duration {
+        self.deadline.saturating_sub(clock)
+    }
+}
@@ -0,0 +1,51 @@
+use ifengine::core::{Achievement, AchievementReport, Achievements, PageId};
+use ifengine::elements::p;
+use ifengine::{ifview, link};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    lit: bool,
+}
+
+#[ifview]
+fn cellar(s: &mut State) {
+    p!(link!("Light the lamp", lamp));
+}
+
+#[ifview]
+fn lamp(s: &mut State) {
+    s.lit = true;
+    p!(link!("Go upstairs", hall));
+}
+
+#[ifview]
+fn hall(s: &mut State) {
+    p!(link!("Go back down", cellar));
+}
+
+fn achievements() -> Achievements<State> {
+    Achievements::new().with(Achievement::new("light", "Let there be light", |s, _| {
+        s.lit
+    }))
+}
+
+#[test]
+fn observed_once_per_path() {
+    let game = ifengine::Game!(cellar);
+    let achievements = achievements();
+    let mut report = AchievementReport::default();
+
+    let mut untouched = true;
+    game.simulate(|s| {
+        achievements.observe(s, &mut report);
+        untouched &= s.tags.iter().next().is_none();
+        s.depth < 10
+    });
+
+    let reach = &report.reachable["light"];
+    assert_eq!(reach.pages.len(), 1);
+    assert!(reach.pages.contains(&PageId::from("achievements::lamp")));
+    assert!(report.unreachable(&achievements).is_empty());
+    // the simulated game is left untouched
+    assert!(untouched);
+}
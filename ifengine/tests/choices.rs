@@ -0,0 +1,135 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use ifengine::core::PageId;
+use ifengine::core::game_state::Selection;
+use ifengine::elements::{ChoiceVariant, dchoice, mchoice, p, read_key, read_selection};
+use ifengine::view::Object;
+use ifengine::{ifview, link};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    gold: usize,
+    wishes: usize,
+    crew: Option<u64>,
+    pick: Option<u16>,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn well(s: &mut State) {
+    p!("The well is deep.");
+    let wishes = ["Wish for gold", "Wish for rain"];
+    dchoice! { ("wish"), wishes,
+        0 => { s.gold += 1 }
+        1 => {}
+    }
+    s.wishes = read_selection!("wish").count(0);
+
+    mchoice!(("crew"), "Oracle", "Walker");
+    s.crew = read_key!("crew");
+    s.pick = read_selection!("crew").last();
+}
+
+fn choices(game: &mut Game) -> Vec<u64> {
+    game.view()
+        .unwrap()
+        .iter()
+        .filter_map(|obj| match obj {
+            Object::Choice(key, _) => Some(*key),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn dchoice_counts_repeated_picks() {
+    let mut game = ifengine::Game!(well);
+    let wish = choices(&mut game)[0];
+
+    for n in 1..=3 {
+        game.handle_choice(("choices::well".into(), wish), 0);
+        // the wish is still offered after each pick
+        assert_eq!(choices(&mut game)[0], wish);
+        assert_eq!(game.context.gold, n);
+        assert_eq!(game.context.wishes, n);
+    }
+}
+
+#[test]
+fn read_key_skips_choices() {
+    let mut game = ifengine::Game!(well);
+    let crew = choices(&mut game)[1];
+    assert_eq!(game.context.crew, None);
+
+    game.handle_choice(("choices::well".into(), crew), 1);
+    choices(&mut game);
+    // a selection isn't a value, it is read with read_selection!
+    assert_eq!(game.context.crew, None);
+    assert_eq!(game.context.pick, Some(1));
+}
+
+#[ifview]
+fn bank(s: &mut State) {
+    mchoice!(
+        ChoiceVariant::disabled(link!("Open the vault", vault), "The vault is locked."),
+        link!("Leave", street)
+    );
+}
+
+#[ifview]
+fn vault(s: &mut State) {
+    p!("Gold, everywhere.");
+}
+
+#[ifview]
+fn street(s: &mut State) {
+    p!("The street is quiet.");
+}
+
+#[test]
+fn sim_skips_disabled_choices() {
+    let game = ifengine::Game!(bank);
+    let sim = game.simulate(|s| s.depth < 10);
+    let run = &sim.runs[""];
+
+    assert!(run.contains_key(&PageId::from("choices::street")));
+    assert!(!run.contains_key(&PageId::from("choices::vault")));
+
+    let bank = run.get(&PageId::from("choices::bank")).unwrap();
+    assert_eq!(bank.disabled["Open the vault"], "The vault is locked.");
+    assert_eq!(bank.never_selectable().count(), 1);
+}
+
+fn hash(selection: &Selection) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    selection.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn selection_ignores_cleared_words() {
+    let mut a = Selection::new();
+    a.set(1);
+    let mut b = a.clone();
+    b.set(300);
+    b.unset(300);
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+}
+
+#[test]
+fn selection_keeps_each_pick_once() {
+    let mut selection = Selection::new();
+    for _ in 0..100 {
+        selection.pick(1);
+    }
+    selection.pick(1000);
+    selection.pick(1);
+
+    assert_eq!(selection.picks().collect::<Vec<_>>(), [1000, 1]);
+    assert_eq!(selection.count(1), 101);
+    assert_eq!(selection.nth(0), Some(1000));
+    assert_eq!(selection.last(), Some(1));
+    assert!(selection.contains(1000));
+}
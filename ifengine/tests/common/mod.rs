@@ -0,0 +1,8 @@
+use ifengine::core::{GameContext, PageId};
+use ifengine::{Action, Game, GameError};
+
+/// Handle an action and view the page it leads to, returning the id of that page.
+pub fn go<C: GameContext>(game: &mut Game<C>, action: Action) -> Result<PageId, GameError> {
+    game.handle_action(action)?;
+    game.view().map(|view| view.name())
+}
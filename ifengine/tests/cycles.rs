@@ -0,0 +1,72 @@
+use ifengine::core::PageId;
+use ifengine::elements::p;
+use ifengine::run::Simulation;
+use ifengine::{END, GameError, SimEnd, ifview, link};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    laps: u32,
+}
+
+#[ifview]
+fn pond(s: &mut State) {
+    p!(link!("Wait", pond));
+}
+
+#[ifview]
+fn hill(s: &mut State) {
+    p!(link!("Down", valley));
+}
+
+#[ifview]
+fn valley(s: &mut State) {
+    p!(link!("Up", hill));
+}
+
+#[ifview]
+fn track(s: &mut State) {
+    s.laps += 1;
+    if s.laps < 3 {
+        p!(link!("Run a lap", track));
+    } else {
+        p!(link!("Rest", finish));
+    }
+}
+
+#[ifview]
+fn finish(s: &mut State) {
+    END!()
+}
+
+fn ends(sim: &Simulation, page: &str) -> Vec<SimEnd> {
+    let record = sim.runs[""].get(&PageId::from(page)).unwrap();
+    record.ends.iter().cloned().collect()
+}
+
+#[test]
+fn self_link() {
+    let sim = ifengine::Game!(pond).simulate(|s| s.depth < 10);
+    assert_eq!(
+        ends(&sim, "cycles::pond"),
+        [SimEnd::Cycle("cycles::pond".into())]
+    );
+}
+
+#[test]
+fn two_page_loop() {
+    let sim = ifengine::Game!(hill).simulate(|s| s.depth < 10);
+    assert_eq!(
+        ends(&sim, "cycles::valley"),
+        [SimEnd::Cycle("cycles::hill".into())]
+    );
+}
+
+#[test]
+fn counter_revisits() {
+    let sim = ifengine::Game!(track).simulate(|s| s.depth < 10);
+    // the ending is reached, and recorded on the page leading to it
+    assert_eq!(
+        ends(&sim, "cycles::track"),
+        [SimEnd::GameError(GameError::End)]
+    );
+}
@@ -0,0 +1,84 @@
+use ifengine::core::Response;
+use ifengine::elements::p;
+use ifengine::{Action, GameError, ifview};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    lost: bool,
+}
+
+#[ifview]
+fn cellar(s: &mut State) {
+    if s.lost {
+        return Response::Exit;
+    }
+    p!("It is dark.");
+}
+
+#[test]
+fn back_beyond_history() {
+    let mut game = ifengine::Game!(cellar);
+    game.view().unwrap();
+
+    let err = game.handle_action(Action::Back(2)).unwrap_err();
+    assert_eq!(
+        err,
+        GameError::BackBeyondHistory {
+            page: "errors::cellar".into(),
+            history: 1,
+            n: 2,
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "Cannot go back 2 page(s) from errors::cellar: only 1 in history."
+    );
+}
+
+#[test]
+fn exit_without_tunnel() {
+    let mut game = ifengine::Game!(cellar);
+    game.view().unwrap();
+
+    let err = game.handle_action(Action::Exit).unwrap_err();
+    assert_eq!(
+        err,
+        GameError::TunnelExitWithoutTunnel {
+            page: "errors::cellar".into(),
+            history: 1,
+        }
+    );
+
+    // the page's own exit is reported the same way
+    game.context.lost = true;
+    assert_eq!(
+        game.view().unwrap_err(),
+        GameError::TunnelExitWithoutTunnel {
+            page: "errors::cellar".into(),
+            history: 1,
+        }
+    );
+}
+
+#[test]
+fn unknown_page_display() {
+    let err = GameError::UnknownPage("errors::attic".into());
+    assert_eq!(err.to_string(), "There is no page called errors::attic.");
+}
+
+#[test]
+fn lost_place_display() {
+    let err = GameError::NoPage {
+        page: "errors::cellar".into(),
+        frames: 2,
+    };
+    assert_eq!(
+        err.to_string(),
+        "The story has lost its place at errors::cellar: there is no page to show."
+    );
+    let err = GameError::NoStack { page: "".into() };
+    assert_eq!(
+        err.to_string(),
+        "The story has lost its place: there is no page stack."
+    );
+}
@@ -0,0 +1,82 @@
+use ifengine::core::Var;
+use ifengine::elements::p;
+use ifengine::{Action, ifview, link};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    coins: u32,
+    steps: u32,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn yard(s: &mut State) {
+    p!(link!("Left door", hall), link!("Right door", hall));
+}
+
+#[ifview]
+fn hall(s: &mut State) {
+    p!("An empty hall.");
+}
+
+fn viewed() -> Game {
+    let mut game = ifengine::Game!(yard);
+    game.view().unwrap();
+    game
+}
+
+fn set(game: &mut Game, name: &str, value: i64) {
+    game.handle_action(Action::SetVar(name.into(), Var::Int(value)))
+        .unwrap();
+}
+
+#[test]
+fn equal_states_match() {
+    let (mut a, mut b) = (viewed(), viewed());
+    assert_eq!(a.fingerprint(), b.fingerprint());
+
+    // the order changes were made in doesn't matter
+    set(&mut a, "x", 1);
+    set(&mut a, "y", 2);
+    set(&mut b, "y", 2);
+    set(&mut b, "x", 1);
+    assert_eq!(a.fingerprint(), b.fingerprint());
+}
+
+#[test]
+fn different_states_differ() {
+    let base = viewed();
+
+    let mut var = viewed();
+    set(&mut var, "x", 1);
+    assert_ne!(base.fingerprint(), var.fingerprint());
+
+    let mut context = viewed();
+    context.context.coins = 1;
+    assert_ne!(base.fingerprint(), context.fingerprint());
+
+    let mut page = viewed();
+    page.jump("fingerprint::hall").unwrap();
+    page.view().unwrap();
+    assert_ne!(base.fingerprint(), page.fingerprint());
+}
+
+#[test]
+fn context_hash_ignores_fields() {
+    let hash: fn(&State) -> u64 = |s| s.coins.into();
+    let base = viewed().with_context_hash(hash);
+    let mut walked = viewed().with_context_hash(hash);
+    walked.context.steps = 3;
+    assert_eq!(base.fingerprint(), walked.fingerprint());
+
+    walked.context.coins = 1;
+    assert_ne!(base.fingerprint(), walked.fingerprint());
+}
+
+#[test]
+fn sim_merges_equal_states() {
+    let sim = ifengine::Game!(yard).simulate(|s| s.depth < 10);
+    // both doors lead to the same hall
+    assert_eq!(sim.merged, 1);
+}
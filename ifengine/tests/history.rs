@@ -0,0 +1,75 @@
+use ifengine::core::PageHandle;
+use ifengine::elements::p;
+use ifengine::{Action, ifview};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    visits: usize,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn start(s: &mut State) {
+    p!("The harbour is quiet.");
+}
+
+#[ifview]
+fn pier(s: &mut State) {
+    s.visits += 1;
+    p!("Gulls circle the pier.");
+}
+
+fn go(game: &mut Game, page: ifengine::core::Page<State>) -> String {
+    game.handle_action(Action::Next(PageHandle::new("".into(), page)))
+        .unwrap();
+    game.view().unwrap().name().to_string()
+}
+
+#[test]
+fn undo_redo() {
+    let mut game = ifengine::Game!(start);
+    game.view().unwrap();
+    assert!(!game.undo());
+
+    assert_eq!(go(&mut game, pier), "history::pier");
+    assert_eq!(game.context.visits, 1);
+
+    assert!(game.undo());
+    assert_eq!(game.context.visits, 0);
+    assert_eq!(&*game.view().unwrap().name(), "history::start");
+    assert!(game.history().can_redo());
+
+    assert!(game.redo());
+    assert_eq!(game.context.visits, 1);
+    assert_eq!(game.id().as_deref(), Some("history::pier"));
+    assert!(!game.redo());
+}
+
+#[test]
+fn new_interaction_clears_future() {
+    let mut game = ifengine::Game!(start);
+    game.view().unwrap();
+    go(&mut game, pier);
+
+    assert!(game.undo());
+    go(&mut game, start);
+    assert!(!game.history().can_redo());
+}
+
+#[test]
+fn capacity() {
+    let mut game = ifengine::Game!(start).with_history(2);
+    game.view().unwrap();
+    go(&mut game, pier);
+    go(&mut game, start);
+
+    assert_eq!(game.history().past().count(), 2);
+    assert!(game.undo());
+    assert!(!game.undo());
+
+    let mut game = ifengine::Game!(start).with_history(0);
+    game.view().unwrap();
+    go(&mut game, pier);
+    assert!(!game.undo());
+}
@@ -0,0 +1,207 @@
+use std::sync::{Arc, Mutex};
+
+use ifengine::core::{Hook, PageHandle, PageId};
+use ifengine::elements::p;
+use ifengine::{Action, END, LINK, TUN, View, ifview, link, tun};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    days: usize,
+    seen: Vec<usize>,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn inn(s: &mut State) {
+    s.seen.push(s.days);
+    p!(link!("Travel", road));
+}
+
+#[ifview]
+fn road(s: &mut State) {
+    s.seen.push(s.days);
+    p!(link!("Rest", inn), link!("Give up", end));
+}
+
+#[ifview]
+fn end(s: &mut State) {
+    END!()
+}
+
+#[ifview]
+fn shortcut(s: &mut State) {
+    LINK!(road)
+}
+
+#[ifview]
+fn dive(s: &mut State) {
+    TUN!(well)
+}
+
+#[ifview]
+fn well(s: &mut State) {
+    p!(tun!("Climb out"));
+}
+
+#[derive(Clone, Default)]
+struct Log(Arc<Mutex<Vec<String>>>);
+
+impl Log {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl Hook<State> for Log {
+    fn on_enter_page(&self, game: &mut Game, page: &PageId) {
+        game.context.days += 1;
+        self.0.lock().unwrap().push(format!("enter {page}"));
+    }
+    fn on_leave_page(&self, _game: &mut Game, page: &PageId) {
+        self.0.lock().unwrap().push(format!("leave {page}"));
+    }
+    fn on_action(&self, _game: &mut Game, action: &Action) {
+        let name = match action {
+            Action::Next(_) => "next",
+            _ => "other",
+        };
+        self.0.lock().unwrap().push(format!("action {name}"));
+    }
+    fn on_view(&self, _game: &mut Game, view: &mut View) {
+        self.0.lock().unwrap().push(format!("view {}", view.pageid));
+    }
+    fn on_end(&self, _game: &mut Game) {
+        self.0.lock().unwrap().push("end".into());
+    }
+}
+
+#[test]
+fn fire_on_transitions() {
+    let log = Log::default();
+    let mut game = ifengine::Game!(inn).with_hook(log.clone());
+
+    // the start page is entered without a page to leave
+    game.view().unwrap();
+    game.view().unwrap();
+    assert_eq!(
+        log.take(),
+        ["enter hooks::inn", "view hooks::inn", "view hooks::inn"]
+    );
+
+    game.handle_action(Action::Next(PageHandle::new("road".into(), road)))
+        .unwrap();
+    assert_eq!(log.take(), ["action next"]);
+
+    // entered once its view resolves the page, so the change shows up from the next view
+    game.view().unwrap();
+    game.view().unwrap();
+    assert_eq!(game.context.days, 2);
+    assert_eq!(game.context.seen[2..], [1, 2]);
+    assert_eq!(
+        log.take(),
+        [
+            "leave hooks::inn",
+            "enter hooks::road",
+            "view hooks::road",
+            "view hooks::road"
+        ]
+    );
+}
+
+#[test]
+fn fire_on_page_responses() {
+    let log = Log::default();
+    let mut game = ifengine::Game!(inn).with_hook(log.clone());
+    game.view().unwrap();
+    log.take();
+
+    // LINK! switches to another page
+    game.handle_action(Action::Next(PageHandle::new("shortcut".into(), shortcut)))
+        .unwrap();
+    game.view().unwrap();
+    assert_eq!(
+        log.take(),
+        [
+            "action next",
+            "leave hooks::inn",
+            "enter hooks::road",
+            "view hooks::road"
+        ]
+    );
+
+    // TUN! enters a tunnel, and exiting it returns to the caller
+    game.handle_action(Action::Next(PageHandle::new("dive".into(), dive)))
+        .unwrap();
+    game.view().unwrap();
+    game.handle_action(Action::Exit).unwrap();
+    game.view().unwrap();
+    assert_eq!(
+        log.take(),
+        [
+            "action next",
+            "leave hooks::road",
+            "enter hooks::well",
+            "view hooks::well",
+            "action other",
+            "leave hooks::well",
+            "enter hooks::road",
+            "view hooks::road"
+        ]
+    );
+}
+
+#[test]
+fn enter_after_restore() {
+    let mut game = ifengine::Game!(inn);
+    game.view().unwrap();
+    game.handle_action(Action::Next(PageHandle::new("road".into(), road)))
+        .unwrap();
+    game.view().unwrap();
+    let save = game.save().unwrap();
+
+    let log = Log::default();
+    let mut game = Game::restore(save, |id| match &**id {
+        "hooks::inn" => Some(inn),
+        "hooks::road" => Some(road),
+        _ => None,
+    })
+    .unwrap()
+    .with_hook(log.clone());
+    game.view().unwrap();
+    game.view().unwrap();
+    assert_eq!(
+        log.take(),
+        ["enter hooks::road", "view hooks::road", "view hooks::road"]
+    );
+}
+
+#[test]
+fn fire_in_simulation() {
+    let log = Log::default();
+    let mut game = ifengine::Game!(inn).with_hook(log.clone());
+    game.view().unwrap();
+    log.take();
+
+    // days keep counting, so the loop between the inn and the road has to be bounded
+    game.simulate(|s| s.depth < 4);
+    let log = log.take();
+    assert!(log.contains(&"enter hooks::road".to_string()));
+    assert!(log.contains(&"leave hooks::road".to_string()));
+    assert!(log.contains(&"end".to_string()));
+}
+
+#[test]
+fn end_fires_once_per_ending() {
+    let log = Log::default();
+    let mut game = ifengine::Game!(road).with_hook(log.clone());
+    game.view().unwrap();
+
+    for _ in 0..2 {
+        game.handle_action(Action::Next(PageHandle::new("end".into(), end)))
+            .unwrap();
+        log.take();
+        assert!(game.view().is_err());
+        assert_eq!(log.take(), ["end"]);
+    }
+}
@@ -0,0 +1,58 @@
+use ifengine::elements::{input, p, reset_key};
+use ifengine::view::Object;
+use ifengine::{Action, ifview};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    answer: Option<String>,
+    retry: bool,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn riddle(s: &mut State) {
+    p!("What has keys but opens no locks?");
+    s.answer = input!(("answer"), "Answer");
+    if s.retry {
+        reset_key!("answer");
+        s.retry = false;
+    }
+}
+
+fn field(game: &mut Game) -> Option<u64> {
+    game.view().unwrap().iter().find_map(|obj| match obj {
+        Object::Input(key, _) => Some(*key),
+        _ => None,
+    })
+}
+
+fn submit(game: &mut Game, value: &str) {
+    let key = field(game).expect("the field is shown");
+    game.handle_action(Action::SetText(("input::riddle".into(), key), value.into()))
+        .unwrap();
+    assert_eq!(field(game), None);
+    assert_eq!(game.context.answer.as_deref(), Some(value));
+}
+
+#[test]
+fn reset_action_clears_text() {
+    let mut game = ifengine::Game!(riddle);
+    let key = field(&mut game).unwrap();
+    submit(&mut game, "a piano");
+
+    game.handle_action(Action::Reset(("input::riddle".into(), key)))
+        .unwrap();
+    assert_eq!(field(&mut game), Some(key));
+    assert_eq!(game.context.answer, None);
+}
+
+#[test]
+fn reset_key_clears_text() {
+    let mut game = ifengine::Game!(riddle);
+    submit(&mut game, "a map");
+
+    game.context.retry = true;
+    game.view().unwrap();
+    assert!(field(&mut game).is_some());
+}
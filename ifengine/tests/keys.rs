@@ -0,0 +1,132 @@
+use ifengine::elements::{choice, key_scope, p};
+use ifengine::ifview;
+use ifengine::view::Object;
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    light: u8,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn crossing(s: &mut State) {
+    p!("The river is high.");
+    choice!("Wade across", "Wait for the ferry");
+    choice!("Look back", "Press on");
+}
+
+// the same elements, reordered and with another one added before them
+#[ifview]
+fn crossing_edited(s: &mut State) {
+    p!("The river is high, and rising.");
+    choice!("Swim", "Turn back");
+    choice!("Look back", "Press on");
+    choice!("Wade across", "Wait for the ferry");
+}
+
+// identical elements are told apart by their order
+#[ifview]
+fn echoes(s: &mut State) {
+    choice!("Shout");
+    choice!("Shout");
+}
+
+// identical elements in exclusive branches don't collide
+#[ifview]
+fn cellar(s: &mut State) {
+    if s.light == 0 {
+        choice!(("door"), "Feel for the door");
+    } else if s.light == 1 {
+        choice!(("door"), "Find the door");
+    } else {
+        choice!(("door"), "Open the door");
+    }
+}
+
+#[ifview]
+fn attic(s: &mut State) {
+    let _opened = match s.light {
+        0 => choice!(("hatch"), "Feel for the hatch"),
+        1 => {
+            p!("A sliver of light.");
+            choice!(("hatch"), "Find the hatch")
+        }
+        _ => choice!(("hatch"), "Open the hatch"),
+    };
+}
+
+fn keys(game: &mut Game) -> Vec<u64> {
+    game.view()
+        .unwrap()
+        .iter()
+        .filter_map(|obj| match obj {
+            Object::Choice(key, _) => Some(*key),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn keys_follow_source() {
+    let original = keys(&mut ifengine::Game!(crossing));
+    assert_eq!(original.len(), 2);
+    assert_ne!(original[0], original[1]);
+
+    let edited = keys(&mut ifengine::Game!(crossing_edited));
+    assert!(!original.contains(&edited[0]));
+    assert_eq!(edited[1..], [original[1], original[0]]);
+
+    let echoes = keys(&mut ifengine::Game!(echoes));
+    assert_ne!(echoes[0], echoes[1]);
+}
+
+#[test]
+fn keys_are_pinned() {
+    // saves rely on keys staying the same across toolchains:
+    // this is the FNV-1a hash of `choice!("Wade across" , "Wait for the ferry" )`
+    let keys = keys(&mut ifengine::Game!(crossing));
+    assert_eq!(keys[0], 8454796756833193559);
+}
+
+#[test]
+fn branches_share_labels() {
+    let mut game = ifengine::Game!(cellar);
+    let dark = keys(&mut game);
+    for light in 1..3 {
+        game.context.light = light;
+        assert_eq!(keys(&mut game), dark);
+    }
+
+    let mut game = ifengine::Game!(attic);
+    let dark = keys(&mut game);
+    for light in 1..3 {
+        game.context.light = light;
+        assert_eq!(keys(&mut game), dark);
+    }
+}
+
+#[ifview]
+fn scoped_break(s: &mut State) {
+    for i in 0..3 {
+        key_scope!(i, {
+            if i == 1 {
+                break;
+            }
+        });
+    }
+    choice!(("after"), "Continue");
+}
+
+#[ifview]
+fn unscoped(s: &mut State) {
+    choice!(("after"), "Continue");
+}
+
+#[test]
+fn key_scope_ends_on_break() {
+    assert_eq!(
+        keys(&mut ifengine::Game!(scoped_break)),
+        keys(&mut ifengine::Game!(unscoped))
+    );
+}
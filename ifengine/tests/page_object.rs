@@ -0,0 +1,73 @@
+use ifengine::core::{PageHandle, PageObject, Response};
+use ifengine::elements::p;
+use ifengine::view::{Object, View};
+use ifengine::{Action, ifview};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    gold: u32,
+}
+
+type Game = ifengine::Game<State>;
+
+struct Shop {
+    stock: Vec<&'static str>,
+}
+
+impl PageObject<State> for Shop {
+    fn call(&self, _game: &mut Game) -> Response {
+        let mut view = View::new("shop".into());
+        for item in &self.stock {
+            view.inner.push(Object::Paragraph((*item).into()));
+        }
+        Response::View(view)
+    }
+}
+
+#[ifview]
+fn street(s: &mut State) {
+    p!("A busy street.");
+}
+
+fn paragraphs(view: &View) -> Vec<String> {
+    view.iter()
+        .filter_map(|obj| match obj {
+            Object::Paragraph(line) => Some(line.content()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn struct_page() {
+    let mut game = ifengine::Game!(street);
+    game.view().unwrap();
+
+    let shop = Shop {
+        stock: vec!["Rope", "Lamp"],
+    };
+    game.handle_action(Action::Next(PageHandle::new_object("shop".into(), shop)))
+        .unwrap();
+    let view = game.view().unwrap();
+    assert_eq!(&*view.name(), "shop");
+    assert_eq!(paragraphs(&view), ["Rope", "Lamp"]);
+
+    game.handle_action(Action::Back(1)).unwrap();
+    assert_eq!(&*game.view().unwrap().name(), "page_object::street");
+}
+
+#[test]
+fn closure_page() {
+    let mut game = ifengine::Game!(street);
+    game.view().unwrap();
+
+    let price = 3;
+    let stall = move |game: &mut Game| {
+        game.context.gold += price;
+        Response::View(View::new("stall".into()))
+    };
+    game.handle_action(Action::Next(PageHandle::new_object("stall".into(), stall)))
+        .unwrap();
+    assert_eq!(&*game.view().unwrap().name(), "stall");
+    assert_eq!(game.context.gold, 3);
+}
@@ -0,0 +1,69 @@
+use ifengine::core::PageId;
+use ifengine::elements::p;
+use ifengine::run::SimulationConfig;
+use ifengine::{SimEnd, ifview, link};
+
+#[derive(Debug, Default, Clone)]
+struct State;
+
+#[ifview]
+fn bridge(s: &mut State) {
+    p!(link!("Cross", chasm), link!("Turn back", road));
+}
+
+#[ifview]
+fn chasm(s: &mut State) {
+    panic!("the bridge gives way")
+}
+
+#[ifview]
+fn road(s: &mut State) {
+    p!("A long road home.");
+}
+
+#[test]
+fn panicking_page() {
+    let sim = ifengine::Game!(bridge).simulate(|s| s.depth < 10);
+    let run = &sim.runs[""];
+
+    let bridge = run.get(&PageId::from("panics::bridge")).unwrap();
+    let ends: Vec<_> = bridge.ends.iter().collect();
+    // the page is named as linked, since it never resolved to a view
+    let [
+        SimEnd::Panic {
+            page,
+            message,
+            path,
+        },
+    ] = &ends[..]
+    else {
+        panic!("expected a single panic, got {ends:?}");
+    };
+    assert!(page.ends_with("chasm"));
+    assert_eq!(message, "the bridge gives way");
+    assert_eq!(path, &[PageId::from("panics::bridge")]);
+    // the other branch is still explored
+    assert!(run.contains_key(&PageId::from("panics::road")));
+}
+
+#[test]
+fn quiet_panics_restore_the_hook() {
+    thread_local! {
+        static PRINTED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+    std::panic::set_hook(Box::new(|_| PRINTED.set(PRINTED.get() + 1)));
+
+    let config = SimulationConfig::new().with_quiet_panics(true);
+    let sim = ifengine::Game!(bridge).simulate_with(&config, |s| s.depth < 10);
+    let bridge = sim.runs[""].get(&PageId::from("panics::bridge")).unwrap();
+    assert!(matches!(
+        bridge.ends.iter().next(),
+        Some(SimEnd::Panic { .. })
+    ));
+    assert_eq!(PRINTED.get(), 0);
+
+    // other panics reach the restored hook
+    let _ = std::panic::catch_unwind(|| panic!("after the run"));
+    assert_eq!(PRINTED.get(), 1);
+    let _ = std::panic::take_hook();
+}
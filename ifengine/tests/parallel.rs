@@ -0,0 +1,127 @@
+use ifengine::core::PageId;
+use ifengine::elements::{click, p, tunnel_result};
+use ifengine::run::SimulationConfig;
+use ifengine::{END, EXIT, GameError, SimEnd, ifview, link, tun};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    coins: u32,
+    rope: bool,
+}
+
+#[ifview]
+fn harbour(s: &mut State) {
+    if let Some(coins) = tunnel_result!(u32) {
+        s.coins = coins;
+    }
+    p!(
+        click!("Buy rope", { s.rope = true }),
+        tun!("Play cards", cards),
+        link!("Climb the cliff", cliff),
+        link!("Board a ship", ship)
+    );
+}
+
+#[ifview]
+fn cards(s: &mut State) {
+    s.coins = 0; // the stake
+    p!(link!("Win", win), link!("Lose", lose));
+}
+
+#[ifview]
+fn win(s: &mut State) {
+    EXIT!(2u32)
+}
+
+#[ifview]
+fn lose(s: &mut State) {
+    EXIT!(0u32)
+}
+
+#[ifview]
+fn cliff(s: &mut State) {
+    if s.rope {
+        p!(link!("Reach the top", summit), link!("Climb down", harbour));
+    } else {
+        p!(link!("Climb down", harbour));
+    }
+}
+
+#[ifview]
+fn tavern(s: &mut State) {
+    if let Some(coins) = tunnel_result!(u32) {
+        s.coins = coins;
+    }
+    p!(
+        tun!("Play cards", cards),
+        link!("Go to the harbour", harbour)
+    );
+}
+
+#[ifview]
+fn summit(s: &mut State) {
+    END!()
+}
+
+#[ifview]
+fn ship(s: &mut State) {
+    if s.coins >= 2 {
+        p!(link!("Sail away", summit));
+    } else {
+        p!(link!("Back ashore", harbour));
+    }
+}
+
+#[test]
+fn matches_sequential() {
+    let game = ifengine::Game!(harbour);
+    let visitor = |s: &mut ifengine::run::SimulationState<State>| s.depth < 12;
+
+    let sequential = game.simulate(visitor);
+    // the story is explored through the tunnel and its result, up to the ending
+    let cards = &sequential.runs["cards"];
+    let cliff = cards.get(&PageId::from("parallel::cliff")).unwrap();
+    assert!(cliff.ends.contains(&SimEnd::GameError(GameError::End)));
+
+    for threads in [1, 2, 4] {
+        let config = SimulationConfig::new().with_threads(threads);
+        let parallel = game.simulate_parallel(&config, visitor);
+        assert_eq!(parallel.runs, sequential.runs);
+        assert_eq!(parallel.status, sequential.status);
+    }
+}
+
+#[test]
+fn matches_sequential_across_callers() {
+    let game = ifengine::Game!(tavern);
+    let visitor = |s: &mut ifengine::run::SimulationState<State>| s.depth < 12;
+
+    let sequential = game.simulate(visitor);
+    // the tunnel is entered from both the tavern and the harbour
+    let cards = &sequential.runs["cards"];
+    for caller in ["parallel::tavern", "parallel::harbour"] {
+        assert!(cards.contains_key(&PageId::from(caller)));
+    }
+
+    for threads in [1, 2, 4] {
+        let config = SimulationConfig::new().with_threads(threads);
+        let parallel = game.simulate_parallel(&config, visitor);
+        assert_eq!(parallel.runs, sequential.runs);
+        assert_eq!(parallel.status, sequential.status);
+        if threads == 1 {
+            // entering with and without coins leads to the same states, which are explored for each entrance
+            assert_eq!(parallel.merged, sequential.merged);
+            assert_eq!(parallel.states, sequential.states);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "lost at sea")]
+fn propagates_worker_panics() {
+    let config = SimulationConfig::new().with_threads(4);
+    ifengine::Game!(harbour).simulate_parallel(&config, |s| {
+        assert!(s.depth < 2, "lost at sea");
+        true
+    });
+}
@@ -0,0 +1,84 @@
+use ifengine::core::{PageHandle, PageId};
+use ifengine::elements::p;
+use ifengine::{Action, GameError, ifview};
+
+mod common;
+use common::go;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Item {
+    Lamp,
+    Rope,
+}
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    inspected: Vec<Item>,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn shelf(s: &mut State) {
+    p!("A dusty shelf.");
+}
+
+#[ifview]
+fn inspect(s: &mut State, item: Item) {
+    s.inspected.push(item);
+    p!(format!("You look at the {item:?}."));
+}
+
+#[test]
+fn params_key_the_page() {
+    let mut game = ifengine::Game!(shelf);
+    game.view().unwrap();
+
+    let lamp = go(
+        &mut game,
+        Action::Next(PageHandle::new("".into(), inspect).with_params(Item::Lamp)),
+    );
+    let rope = go(
+        &mut game,
+        Action::Next(PageHandle::new("".into(), inspect).with_params(Item::Rope)),
+    );
+    let lamp = lamp.unwrap();
+    assert_eq!(lamp, PageId::with_params("params::inspect", &Item::Lamp));
+    assert!(lamp.has_params());
+    assert_ne!(rope.unwrap(), lamp);
+
+    game.handle_action(Action::Back(1)).unwrap();
+    assert_eq!(game.view().unwrap().name(), lamp);
+    assert_eq!(game.context.inspected, [Item::Lamp, Item::Rope, Item::Lamp]);
+}
+
+#[test]
+fn missing_or_mistyped_params() {
+    let expected = GameError::MissingParams {
+        page: "params::inspect".into(),
+        expected: "Item".into(),
+    };
+
+    let mut game = ifengine::Game!(inspect);
+    assert_eq!(game.view().unwrap_err(), expected);
+
+    let mut game = ifengine::Game!(shelf);
+    game.view().unwrap();
+    let wrong = PageHandle::new("".into(), inspect).with_params(3u8);
+    assert_eq!(go(&mut game, Action::Next(wrong)), Err(expected));
+}
+
+#[test]
+fn restore_rejects_params() {
+    let mut game = ifengine::Game!(shelf);
+    game.view().unwrap();
+    let lamp = go(
+        &mut game,
+        Action::Next(PageHandle::new("".into(), inspect).with_params(Item::Lamp)),
+    )
+    .unwrap();
+
+    let save = game.save().unwrap();
+    let err = Game::restore(save, |_| Some(shelf)).unwrap_err();
+    assert_eq!(err, GameError::ParamsNotSaved(lamp));
+}
@@ -0,0 +1,51 @@
+use ifengine::core::Registry;
+use ifengine::elements::p;
+use ifengine::{GameError, ifview};
+
+#[derive(Debug, Default, Clone)]
+struct State;
+
+#[derive(Debug, Default, Clone)]
+struct Other;
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn gate(s: &mut State) {
+    p!("The gate is shut.");
+}
+
+#[ifview]
+fn yard(s: &mut State) {
+    p!("The yard is empty.");
+}
+
+#[ifview]
+fn elsewhere(s: &mut Other) {
+    p!("Another story.");
+}
+
+#[test]
+fn collects_pages_by_context() {
+    let registry = Registry::<State>::new();
+    let ids: Vec<_> = registry.ids().map(|id| id.to_string()).collect();
+    assert_eq!(ids, ["registry::gate", "registry::yard"]);
+
+    assert!(Registry::<Other>::new().contains("registry::elsewhere"));
+    assert!(!registry.contains("registry::elsewhere"));
+}
+
+#[test]
+fn jump_and_new_with_id() {
+    let mut game = Game::new_with_id("registry::gate").unwrap();
+    assert_eq!(&*game.view().unwrap().name(), "registry::gate");
+
+    game.jump("registry::yard").unwrap();
+    assert_eq!(&*game.view().unwrap().name(), "registry::yard");
+
+    assert_eq!(
+        game.jump("registry::elsewhere"),
+        Err(GameError::UnknownPage("registry::elsewhere".into()))
+    );
+    assert!(Game::new_with_id("registry::missing").is_err());
+}
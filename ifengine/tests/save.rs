@@ -0,0 +1,120 @@
+use ifengine::core::{Page, PageHandle};
+use ifengine::elements::p;
+use ifengine::{Action, GameError, LINK, ifview};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct State {
+    visits: usize,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn start(s: &mut State) {
+    p!("The harbour is quiet.");
+}
+
+#[ifview]
+fn pier(s: &mut State) {
+    s.visits += 1;
+    p!("Gulls circle the pier.");
+}
+
+#[ifview]
+fn ferry(s: &mut State) {
+    LINK!(pier)
+}
+
+fn resolve(id: &str) -> Option<Page<State>> {
+    match id {
+        "save::start" => Some(start),
+        "save::pier" => Some(pier),
+        _ => None,
+    }
+}
+
+fn played() -> Game {
+    let mut game = ifengine::Game!(start, 7);
+    game.view().unwrap();
+    game.handle_action(Action::Next(PageHandle::new("pier".into(), pier)))
+        .unwrap();
+    game.view().unwrap();
+    game
+}
+
+#[test]
+fn round_trip() {
+    let game = played();
+    let save = game.save().unwrap();
+    assert_eq!(
+        save.pages,
+        vec![vec!["save::start".into(), "save::pier".into()]]
+    );
+
+    let mut restored = Game::restore(save, |id| resolve(id)).unwrap();
+    assert_eq!(restored.context, game.context);
+    assert_eq!(restored.seed(), 7);
+
+    let view = restored.view().unwrap();
+    assert_eq!(&*view.name(), "save::pier");
+
+    restored.handle_action(Action::Back(1)).unwrap();
+    assert_eq!(&*restored.view().unwrap().name(), "save::start");
+}
+
+#[test]
+fn save_resolves_queued_page() {
+    let mut game = ifengine::Game!(start);
+    game.view().unwrap();
+    game.handle_action(Action::Next(PageHandle::new("pier".into(), pier)))
+        .unwrap();
+
+    let save = game.save().unwrap();
+    assert_eq!(
+        save.pages,
+        vec![vec!["save::start".into(), "save::pier".into()]]
+    );
+    // the page is looked up, not called
+    assert_eq!(save.context.visits, 0);
+
+    let mut restored = Game::restore(save, |id| resolve(id)).unwrap();
+    restored.view().unwrap();
+    assert_eq!(restored.context.visits, 1);
+    assert!(restored.fresh());
+}
+
+#[test]
+fn save_keeps_queued_link() {
+    let mut game = ifengine::Game!(start);
+    game.view().unwrap();
+    game.handle_action(Action::Next(PageHandle::new("ferry".into(), ferry)))
+        .unwrap();
+
+    let save = game.save().unwrap();
+    assert_eq!(
+        save.pages,
+        vec![vec!["save::start".into(), "save::ferry".into()]]
+    );
+    let mut restored = Game::restore_registered(save).unwrap();
+    assert_eq!(&*restored.view().unwrap().name(), "save::pier");
+}
+
+#[test]
+fn save_rejects_unregistered_page() {
+    let mut game = ifengine::Game!(start);
+    game.view().unwrap();
+    let dock = |game: &mut Game| pier(game);
+    game.handle_action(Action::Next(PageHandle::new_object("dock".into(), dock)))
+        .unwrap();
+    assert_eq!(game.save().unwrap_err(), GameError::PendingPage);
+}
+
+#[test]
+fn unknown_page() {
+    let save = played().save().unwrap();
+    let err = Game::restore(save, |id| {
+        (&**id != "save::pier").then_some(start as Page<State>)
+    })
+    .unwrap_err();
+    assert_eq!(err, GameError::UnknownPage("save::pier".into()));
+}
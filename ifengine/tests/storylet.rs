@@ -0,0 +1,152 @@
+use ifengine::core::{Deck, PageId, Storylet};
+use ifengine::elements::p;
+use ifengine::{DRAW, GameError, ifview};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    miles: usize,
+    rations: usize,
+    fallback: bool,
+}
+
+type Game = ifengine::Game<State>;
+
+fn road() -> Deck<State> {
+    Deck::new()
+        .with(Storylet::new(bandits).with_condition(|s, _| s.rations > 3))
+        .with(Storylet::new(storm).with_condition(|s, _| s.rations > 0 && s.miles < 100))
+        .with(
+            Storylet::new(arrive)
+                .with_condition(|s, _| s.miles >= 100)
+                .with_priority(1),
+        )
+}
+
+#[ifview]
+fn travel(s: &mut State) {
+    if s.fallback {
+        DRAW!(road().with_fallback(camp))
+    }
+    DRAW!(road())
+}
+
+#[ifview]
+fn bandits(s: &mut State) {
+    p!("Bandits!");
+}
+
+#[ifview]
+fn storm(s: &mut State) {
+    p!("A storm rolls in.");
+}
+
+#[ifview]
+fn arrive(s: &mut State) {
+    p!("The city walls rise ahead.");
+}
+
+#[ifview]
+fn camp(s: &mut State) {
+    p!("You make camp.");
+}
+
+fn draw(state: State) -> Result<String, GameError> {
+    let mut game: Game = ifengine::Game!(travel, 3);
+    game.context = state;
+    game.view().map(|view| view.name().to_string())
+}
+
+#[test]
+fn draws_eligible() {
+    let state = State {
+        miles: 10,
+        rations: 1,
+        ..Default::default()
+    };
+    assert_eq!(draw(state).unwrap(), "storylet::storm");
+}
+
+#[test]
+fn highest_priority_wins() {
+    let state = State {
+        miles: 120,
+        rations: 5,
+        ..Default::default()
+    };
+    assert_eq!(draw(state).unwrap(), "storylet::arrive");
+}
+
+#[test]
+fn reproducible() {
+    let state = State {
+        miles: 10,
+        rations: 5,
+        ..Default::default()
+    };
+    let first = draw(state.clone()).unwrap();
+    assert!(["storylet::storm", "storylet::bandits"].contains(&first.as_str()));
+    for _ in 0..5 {
+        assert_eq!(draw(state.clone()).unwrap(), first);
+    }
+}
+
+#[test]
+fn fallback_or_end() {
+    let stranded = State {
+        miles: 10,
+        ..Default::default()
+    };
+    assert_eq!(draw(stranded.clone()), Err(GameError::End));
+
+    let state = State {
+        fallback: true,
+        ..stranded
+    };
+    assert_eq!(draw(state).unwrap(), "storylet::camp");
+}
+
+#[test]
+fn simulation_reaches_every_draw() {
+    let mut game: Game = ifengine::Game!(travel, 3);
+    game.context = State {
+        miles: 10,
+        rations: 5,
+        ..Default::default()
+    };
+
+    let sim = game.simulate(|_| true);
+    let records = &sim.runs[""];
+    for page in ["storylet::bandits", "storylet::storm"] {
+        assert!(
+            records.contains_key(&PageId::from(page)),
+            "{page} not reached"
+        );
+    }
+}
+
+#[test]
+fn picks_are_pinned() {
+    use ifengine::utils::{pick, pick_weighted};
+
+    // a loaded save replays its draws only if a seed keeps picking the same index
+    let picks: Vec<_> = (0..8).map(|seed| pick(seed, 10).unwrap()).collect();
+    assert_eq!(picks, [8, 8, 5, 5, 7, 0, 9, 9]);
+    let weighted: Vec<_> = (0..8)
+        .map(|seed| pick_weighted(seed, &[1, 0, 3]).unwrap())
+        .collect();
+    assert_eq!(weighted, [2, 2, 2, 2, 2, 0, 2, 2]);
+
+    assert_eq!(pick(0, 0), None);
+    assert_eq!(pick_weighted(0, &[0, 0]), None);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn draws_are_pinned() {
+    let state = State {
+        miles: 10,
+        rations: 5,
+        ..Default::default()
+    };
+    assert_eq!(draw(state).unwrap(), "storylet::storm");
+}
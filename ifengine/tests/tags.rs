@@ -0,0 +1,70 @@
+use ifengine::core::{PageHandle, PageId};
+use ifengine::elements::{p, tag};
+use ifengine::view::TagKind;
+use ifengine::{Action, ifview, link};
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    storm: bool,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn cove(s: &mut State) {
+    tag!("first_sight", Once);
+    p!(link!("Walk along the shore", shore));
+}
+
+#[ifview]
+fn shore(s: &mut State) {
+    p!(link!("Return to the cove", cove));
+}
+
+#[ifview]
+fn cliff(s: &mut State) {
+    if s.storm {
+        tag!("rain", Sticky);
+    } else {
+        tag!("rain");
+    }
+    p!(link!("Wait", cliff_wait));
+}
+
+#[ifview]
+fn cliff_wait(s: &mut State) {
+    s.storm = !s.storm;
+    p!(link!("Look again", cliff));
+}
+
+fn has_tag(game: &mut Game, tag: &str) -> bool {
+    let view = game.view().unwrap();
+    view.tags.iter().any(|(t, _)| **t == *tag)
+}
+
+#[test]
+fn once_is_kept_for_the_visit() {
+    let mut game = ifengine::Game!(cove);
+    assert!(has_tag(&mut game, "first_sight"));
+    // rendering the same visit again, as frontends do every frame
+    assert!(has_tag(&mut game, "first_sight"));
+
+    game.handle_action(Action::Next(PageHandle::new("".into(), shore)))
+        .unwrap();
+    assert!(!has_tag(&mut game, "first_sight"));
+    game.handle_action(Action::Next(PageHandle::new("".into(), cove)))
+        .unwrap();
+    assert!(!has_tag(&mut game, "first_sight"));
+    assert!(!has_tag(&mut game, "first_sight"));
+}
+
+#[test]
+fn conflicting_kinds_keep_the_most_persistent() {
+    for storm in [false, true] {
+        let mut game = ifengine::Game!(cliff);
+        game.context.storm = storm;
+        let sim = game.simulate(|s| s.depth < 10);
+        let record = sim.runs[""].get(&PageId::from("tags::cliff")).unwrap();
+        assert_eq!(record.tags[&PageId::from("rain")], TagKind::Sticky);
+    }
+}
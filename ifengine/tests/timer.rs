@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use ifengine::core::PageHandle;
+use ifengine::elements::{after, p, timed_choice, weave};
+use ifengine::{Action, ifview, link};
+
+mod common;
+use common::go;
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    jumped: bool,
+    fell: bool,
+}
+
+#[ifview]
+fn bridge(s: &mut State) {
+    p!("The bridge creaks.");
+    timed_choice! {
+        5.0, 1,
+        "Jump" => {
+            s.jumped = true;
+            "You clear the gap."
+        },
+        "Hesitate" => {
+            s.fell = true;
+            "The bridge gives way beneath you."
+        },
+    };
+    p!(link!("Step back", ledge));
+}
+
+#[ifview]
+fn ledge(s: &mut State) {
+    timed_choice! { ("wait"), 1.0, 0, "Wait" };
+    p!(link!("Try the bridge again", bridge));
+}
+
+#[ifview]
+fn camp(s: &mut State) {
+    p!("The fire crackles.");
+    weave!(fuse);
+}
+
+#[ifview]
+fn fuse(s: &mut State) {
+    timed_choice! {
+        5.0, 0,
+        "Stamp it out" => {
+            s.fell = true;
+            "The fuse sputters out."
+        },
+    };
+}
+
+#[ifview]
+fn dark(s: &mut State) {
+    s.fell = after!(-1.0);
+    s.jumped = after!(f64::INFINITY);
+    timed_choice! { f64::NAN, 0, "Wait" => "Nothing moves." };
+}
+
+#[test]
+fn resolves_to_default() {
+    let mut game = ifengine::Game!(bridge);
+    game.view().unwrap();
+
+    assert!(!game.tick(Duration::from_secs(4)).unwrap());
+    assert!(game.tick(Duration::from_secs(2)).unwrap());
+    game.view().unwrap();
+    assert!(game.context.fell);
+    assert!(!game.context.jumped);
+}
+
+#[test]
+fn restarts_on_entry() {
+    let mut game = ifengine::Game!(bridge);
+    game.view().unwrap();
+    game.tick(Duration::from_secs(3)).unwrap();
+    // rendering the same visit again keeps the countdown
+    game.view().unwrap();
+    assert_eq!(game.next_timer(), Some(Duration::from_secs(2)));
+
+    go(&mut game, Action::Next(PageHandle::new("".into(), ledge))).unwrap();
+    game.tick(Duration::from_secs(10)).unwrap();
+    go(&mut game, Action::Next(PageHandle::new("".into(), bridge))).unwrap();
+
+    assert_eq!(game.next_timer(), Some(Duration::from_secs(5)));
+    assert!(!game.context.fell);
+}
+
+#[test]
+fn threads_keep_the_visit() {
+    let mut game = ifengine::Game!(camp);
+    game.view().unwrap();
+    game.tick(Duration::from_secs(3)).unwrap();
+    // the thread is re-rendered as part of the same visit to the camp
+    game.view().unwrap();
+    assert_eq!(game.next_timer(), Some(Duration::from_secs(2)));
+
+    assert!(game.tick(Duration::from_secs(2)).unwrap());
+    game.view().unwrap();
+    assert!(game.context.fell);
+}
+
+#[test]
+fn odd_timeouts_dont_panic() {
+    let mut game = ifengine::Game!(dark);
+    game.view().unwrap();
+    assert!(game.context.fell);
+    assert!(!game.context.jumped);
+    // the NaN timeout is due at once
+    assert!(game.tick(Duration::ZERO).unwrap());
+    game.view().unwrap();
+    assert!(!game.context.jumped);
+}
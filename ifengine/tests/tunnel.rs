@@ -0,0 +1,97 @@
+use ifengine::core::{PageHandle, PageId};
+use ifengine::elements::{click, p, tunnel_result};
+use ifengine::{Action, EXIT, TUN, ifview, link, tun};
+
+mod common;
+use common::go;
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    page_tunnel: bool,
+    seated: bool,
+}
+
+type Game = ifengine::Game<State>;
+
+#[ifview]
+fn tavern(s: &mut State) {
+    match tunnel_result!(bool) {
+        Some(true) => {
+            p!(link!("Celebrate", party));
+        }
+        Some(false) => {
+            p!(link!("Sulk", street));
+        }
+        None if s.page_tunnel => {
+            p!(click!("Sit down", { s.seated = true }));
+            // the handler runs on the click's render
+            if s.seated {
+                TUN!(dice)
+            }
+        }
+        None => {
+            p!(tun!("Play dice", dice));
+        }
+    }
+}
+
+#[ifview]
+fn dice(s: &mut State) {
+    p!(link!("Win", win), link!("Lose", lose));
+}
+
+#[ifview]
+fn win(s: &mut State) {
+    EXIT!(true)
+}
+
+#[ifview]
+fn lose(s: &mut State) {
+    EXIT!(false)
+}
+
+#[ifview]
+fn party(s: &mut State) {
+    p!("Drinks all round.");
+}
+
+#[ifview]
+fn street(s: &mut State) {
+    p!(link!("Back inside", tavern));
+}
+
+fn reached(game: &Game, id: &str) -> bool {
+    let sim = game.simulate(|s| s.depth < 10);
+    let id = PageId::from(id);
+    sim.runs.values().any(|run| run.contains_key(&id))
+}
+
+#[test]
+fn result_is_cleared_after_leaving() {
+    let mut game = ifengine::Game!(tavern);
+    game.view().unwrap();
+
+    go(&mut game, Action::Tunnel(PageHandle::new("".into(), dice))).unwrap();
+    let back = go(&mut game, Action::Next(PageHandle::new("".into(), lose))).unwrap();
+    assert_eq!(back, PageId::from("tunnel::tavern"));
+    // still shown while the caller is
+    assert_eq!(game.view().unwrap().name().to_string(), "tunnel::tavern");
+    assert!(game.inner.tunnel_result("tunnel::tavern").is_some());
+
+    go(&mut game, Action::Next(PageHandle::new("".into(), street))).unwrap();
+    assert!(game.inner.tunnel_result("tunnel::tavern").is_none());
+    go(&mut game, Action::Next(PageHandle::new("".into(), tavern))).unwrap();
+    assert!(game.inner.tunnel_result("tunnel::tavern").is_none());
+}
+
+#[test]
+fn sim_explores_results() {
+    let game = ifengine::Game!(tavern);
+    assert!(reached(&game, "tunnel::party"));
+    assert!(reached(&game, "tunnel::street"));
+
+    let mut game = ifengine::Game!(tavern);
+    game.context.page_tunnel = true;
+    assert!(reached(&game, "tunnel::party"));
+    assert!(reached(&game, "tunnel::street"));
+}
@@ -0,0 +1,38 @@
+use ifengine::core::{Var, Vars};
+use ifengine::elements::{p, set_var, var};
+use ifengine::ifview;
+
+#[derive(Debug, Default, Clone)]
+struct State {
+    visits: usize,
+}
+
+#[ifview]
+fn market(s: &mut State) {
+    s.visits += 1;
+    set_var!("met_elias", true);
+    set_var!("gold", 12);
+    let gold = var!("gold").and_then(Var::as_int).unwrap_or(0);
+    p!(format!("You have {gold} gold."));
+}
+
+#[test]
+fn set_from_page() {
+    let mut game = ifengine::Game!(market);
+    game.view().unwrap();
+
+    assert_eq!(game.inner.vars.get("gold"), Some(&Var::Int(12)));
+    assert_eq!(game.inner.vars.get("met_elias"), Some(&Var::Bool(true)));
+}
+
+#[test]
+fn inc_saturates() {
+    let mut vars = Vars::new();
+    vars.set("gold", i64::MAX - 1);
+    vars.inc("gold", 5);
+    assert_eq!(vars.get("gold"), Some(&Var::Int(i64::MAX)));
+
+    vars.set("debt", i64::MIN);
+    vars.inc("debt", -1);
+    assert_eq!(vars.get("debt"), Some(&Var::Int(i64::MIN)));
+}
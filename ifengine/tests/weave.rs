@@ -0,0 +1,79 @@
+use ifengine::core::PageId;
+use ifengine::elements::{p, weave};
+use ifengine::{BACK, GameError, LINK, ifview};
+
+#[derive(Debug, Default, Clone)]
+struct State;
+
+#[ifview]
+fn camp(s: &mut State) {
+    p!("The fire crackles.");
+    weave!(elias, scout);
+}
+
+#[ifview]
+fn elias(s: &mut State) {
+    p!("Elias is sharpening his knife.");
+}
+
+#[ifview]
+fn mirror(s: &mut State) {
+    weave!(mirror);
+}
+
+#[ifview]
+fn north(s: &mut State) {
+    weave!(south);
+}
+
+#[ifview]
+fn south(s: &mut State) {
+    weave!(north);
+}
+
+// a linked thread is replaced by the page it links to
+#[ifview]
+fn scout(s: &mut State) {
+    LINK!(elias)
+}
+
+#[ifview]
+fn deserter(s: &mut State) {
+    BACK!()
+}
+
+#[ifview]
+fn watch(s: &mut State) {
+    p!("Someone should keep watch.");
+    weave!(deserter);
+}
+
+#[test]
+fn threads_add_to_the_page() {
+    let view = ifengine::Game!(camp).view().unwrap();
+    assert_eq!(view.pageid, PageId::from("weave::camp"));
+    assert_eq!(view.len(), 3);
+}
+
+#[test]
+fn rejects_pages_woven_into_themselves() {
+    assert_eq!(
+        ifengine::Game!(mirror).view().unwrap_err(),
+        GameError::WovenIntoItself("weave::mirror".into())
+    );
+    assert_eq!(
+        ifengine::Game!(north).view().unwrap_err(),
+        GameError::WovenIntoItself("weave::north".into())
+    );
+}
+
+#[test]
+fn threads_cant_leave_the_page() {
+    assert_eq!(
+        ifengine::Game!(watch).view().unwrap_err(),
+        GameError::ThreadLeftPage {
+            thread: "deserter".into(),
+            page: "weave::watch".into(),
+        }
+    );
+}
@@ -15,12 +15,25 @@ use nodes::*;
 /// The function must take your game state as a parameter, and return `()`.
 /// This macro will rewrite your function to receive a &mut [`ifengine::Game`] and return a [`ifengine::core::Response`], as well as enabling usage of [`ifengine::elements`] to produce that response (which in most cases will be a [`ifengine::View`]).
 ///
+/// The page is also registered under `module_path!()::name`, so that it can be looked up through [`ifengine::core::Registry`].
+///
+/// An optional second parameter receives the [params](ifengine::core::PageHandle::with_params) passed by `link!`, `tun!` or `LINK!`.
+/// Its type must be `Clone + Hash`, and a stable hash of it is appended to the page id (see [`ifengine::core::PageId::with_params`]), so that each set of params has its own page state.
+/// Such pages are not registered, since they cannot be called by id alone, and are not restored from saves.
+/// Calling one without params of its type fails with [`ifengine::GameError::MissingParams`].
+///
 /// # Examples
 ///```rust
 /// #[ifview]
 /// pub fn p1(s: &mut State) {
 ///     h!("SALTWRACK", 3); // heading level 3
 ///     p!(link!("BEGIN", p2)); // Link to the next page
+///     p!(link!("Inspect the lamp", inspect, ItemId::Lamp));
+/// }
+///
+/// #[ifview]
+/// pub fn inspect(s: &mut State, item: ItemId) {
+///     p!(s.items[item].description);
 /// }
 ///
 /// // ----- mod.rs -----
@@ -36,10 +49,10 @@ pub fn ifview(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let name = &input.sig.ident;
     let original_block = &input.block;
 
-    if input.sig.inputs.len() != 1 {
+    if !(1..=2).contains(&input.sig.inputs.len()) {
         return Error::new_spanned(
             &input.sig.inputs,
-            "ifview functions must have exactly one input: the context type C",
+            "ifview functions must take the context type C, and optionally the page params",
         )
         .to_compile_error()
         .into();
@@ -57,6 +70,59 @@ pub fn ifview(_attr: TokenStream, item: TokenStream) -> TokenStream {
             .into();
     };
 
+    let params = match input.sig.inputs.iter().nth(1) {
+        Some(syn::FnArg::Typed(pat_type)) => Some(pat_type),
+        Some(arg) => {
+            return Error::new_spanned(arg, "Expected a typed parameter")
+                .to_compile_error()
+                .into();
+        }
+        None => None,
+    };
+
+    let original_block = assign_keys(quote!(#original_block));
+    if let Err(e) = check_duplicate_keys(original_block.clone()) {
+        return e.to_compile_error().into();
+    }
+
+    let (bind_params, pageid) = if let Some(syn::PatType { pat, ty, .. }) = params {
+        (
+            quote! {
+                let Some(__ifengine_params) = __ifengine_game.params::<#ty>().cloned() else {
+                    return ifengine::core::Response::Error(ifengine::GameError::MissingParams {
+                        page: concat!(module_path!(), "::", stringify!(#name)).into(),
+                        expected: stringify!(#ty).into(),
+                    });
+                };
+                let __ifengine_pageid = ifengine::core::PageId::with_params(
+                    concat!(module_path!(), "::", stringify!(#name)),
+                    &__ifengine_params,
+                );
+                #[allow(unused_variables)]
+                let #pat = __ifengine_params;
+            },
+            quote!(__ifengine_pageid),
+        )
+    } else {
+        (
+            quote!(),
+            quote!(concat!(module_path!(), "::", stringify!(#name))),
+        )
+    };
+
+    let register = if params.is_none() {
+        quote! {
+            ifengine::__private::inventory::submit! {
+                ifengine::core::RegisteredPage {
+                    id: concat!(module_path!(), "::", stringify!(#name)),
+                    page: &(#name as ifengine::core::Page<#ctx_type>),
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
     let expanded = quote! {
         pub fn #name(__ifengine_game: &mut ifengine::Game<#ctx_type>)
         -> ifengine::core::Response
@@ -66,21 +132,32 @@ pub fn ifview(_attr: TokenStream, item: TokenStream) -> TokenStream {
             let #ctx_arg = &mut __ifengine_game.context;
             let __ifengine_game_tags = &mut __ifengine_game.tags;
             let __ifengine_game = &mut __ifengine_game.inner;
+            #bind_params
+            let __ifengine_seed = __ifengine_game.page_seed(&#pageid);
+            let __ifengine_tunnel_result = __ifengine_game.tunnel_result(&#pageid);
+            let __ifengine_clock = __ifengine_game.clock();
+            let __ifengine_showing = __ifengine_game.showing(&#pageid);
             let mut __ifengine_page_state = ifengine::core::PageState::new(
 
-                format!("{}::{}", module_path!(), stringify!(#name)),
+                format!("{}", #pageid),
                 __ifengine_game.fresh(),
                 __ifengine_simulating,
-                __ifengine_game.state.get_page_mut(format!("{}::{}", module_path!(), stringify!(#name))),
+                __ifengine_game.state.get_page_mut(format!("{}", #pageid)),
                 __ifengine_game_tags,
 
-            );
+            )
+            .with_seed(__ifengine_seed)
+            .with_tunnel_result(__ifengine_tunnel_result)
+            .with_showing(__ifengine_showing)
+            .with_clock(__ifengine_clock);
 
             #original_block
 
             #[allow(unreachable_code)]
             __ifengine_page_state.into_response()
         }
+
+        #register
     };
 
     expanded.into()
@@ -115,7 +192,15 @@ struct ChoiceInput {
 impl Parse for ChoiceInput {
     fn parse(input: ParseStream) -> Result<Self> {
         let maybe_key = input.parse()?;
+        let arms = ChoiceInput::parse_arms(input)?;
+
+        Ok(ChoiceInput { maybe_key, arms })
+    }
+}
 
+impl ChoiceInput {
+    /// The `LHS => RHS` arms following the key.
+    fn parse_arms(input: ParseStream) -> Result<Vec<LineArm>> {
         let mut arms = Vec::new();
         while !input.is_empty() {
             let mut lhs_exprs = vec![input.parse::<Expr>()?];
@@ -141,7 +226,12 @@ impl Parse for ChoiceInput {
             input.parse::<Token![,]>().ok();
         }
 
-        Ok(ChoiceInput { maybe_key, arms })
+        // choice ids are u16
+        if arms.len() > 1 << 16 {
+            return Err(input.error("a choice set can have at most 65536 options"));
+        }
+
+        Ok(arms)
     }
 }
 
@@ -159,7 +249,7 @@ impl Parse for ChoiceInput {
 ///
 /// # Additional
 /// A [`MaybeKey`] can be specified as the first argument
-///   When a choice is clicked, it sets the value of its key to (the u8 value of) its id in [`PageState`].
+///   When a choice is clicked, its id is picked in the [`Selection`](ifengine::core::game_state::Selection) of its key in [`PageState`].
 ///   It is discouraged to specify this: by default, it will be automatically generated.
 /// Multiple LHS values can be specified for the same RHS using `|`
 ///
@@ -176,15 +266,20 @@ impl Parse for ChoiceInput {
 pub fn choice(input: TokenStream) -> TokenStream {
     let ChoiceInput { maybe_key, arms } = syn::parse_macro_input!(input as ChoiceInput);
 
-    let key_tokens = maybe_key.into_tokens();
+    choice_tokens(maybe_key.into_tokens(), &arms).into()
+}
 
+fn choice_tokens(
+    key_tokens: proc_macro2::TokenStream,
+    arms: &[LineArm],
+) -> proc_macro2::TokenStream {
     let mut index_arms = Vec::new();
     let mut lines = Vec::new();
 
     for (i, LineArm { line, block }) in arms.iter().enumerate() {
-        let i = i as u8;
+        let i = i as u16;
 
-        lines.push(quote! { (#i, ifengine::view::Line::from(#line)) });
+        lines.push(quote! { (#i, ifengine::view::Line::from(#line), None) });
 
         let block_tokens = match block {
             Some(b) => quote! { ifengine::view::Line::from({ #b }) },
@@ -221,7 +316,7 @@ pub fn choice(input: TokenStream) -> TokenStream {
         }
     };
 
-    expanded.into()
+    expanded
 }
 
 /// Execute a set of conditional expressions based on user-selected choices.
@@ -233,6 +328,7 @@ pub fn choice(input: TokenStream) -> TokenStream {
 /// Each LHS key is a [`ifengine::elements::ChoiceVariant`], dictating its visibility.
 /// Any type that implements `Into<`[`Line`](ifengine::view::Line)`>` will coerce to `Choice::Once`.
 /// Any `Option<Into<Line>>` will coerce to `Choice::None` or `Choice::Always`.
+/// Use `ChoiceVariant::disabled(line, reason)` to show a choice which cannot be selected.
 ///
 /// The return type is a [bool; n] representing which of the options were hidden (NOT displayed).
 ///
@@ -259,7 +355,7 @@ pub fn mchoice(input: TokenStream) -> TokenStream {
         .iter()
         .enumerate()
         .map(|(i, LineArm { line, block })| {
-            let i = i as u8;
+            let i = i as u16;
 
             let block_tokens = match block {
                 Some(b) => quote! { #b },
@@ -267,13 +363,13 @@ pub fn mchoice(input: TokenStream) -> TokenStream {
             };
 
             quote! {
-                if (__ifengine_tmp_mask & (1u64 << #i)) != 0 {
+                if __ifengine_tmp_mask.contains(#i as usize) {
                     #block_tokens
                 }
-                if let Some(l) = ifengine::elements::ChoiceVariant::from(#line)
-                .as_line((__ifengine_tmp_mask & (1u64 << #i)) != 0)
+                if let Some((l, reason)) = ifengine::elements::ChoiceVariant::from(#line)
+                .as_line(__ifengine_tmp_mask.contains(#i as usize))
                 {
-                    __ifengine_tmp_lines.push((#i, l));
+                    __ifengine_tmp_lines.push((#i, l, reason));
                     __ifengine_visible_mask[#i as usize] = false;
                 }
             }
@@ -284,7 +380,7 @@ pub fn mchoice(input: TokenStream) -> TokenStream {
 
     let expanded = quote! {
         {
-            let __ifengine_tmp_mask = __ifengine_page_state.get(#key).unwrap_or(0u64);
+            let __ifengine_tmp_mask = __ifengine_page_state.selection(#key).unwrap_or_default();
             let mut __ifengine_tmp_lines = Vec::new();
             let mut __ifengine_visible_mask = [true; #n];
 
@@ -323,8 +419,9 @@ pub fn mchoice(input: TokenStream) -> TokenStream {
 ///
 /// # Additional
 /// A [`MaybeKey`] can be specified in the first argument:
-///   When a choice is clicked, it sets the value of its key to its id (cast as a u8) in [`PageState`].
-///   When the page is next rendered, this value is removed, and the corresponding match arm is run.
+///   When a choice is clicked, its id (cast as a u16) is picked in the [`Selection`](ifengine::core::game_state::Selection) of its key in [`PageState`].
+///   When the page is next rendered, the option is deselected, and the corresponding match arm is run.
+///   The pick itself is kept, so [`read_selection`] can count how many times each option was picked.
 ///   It is discouraged to specify this: by default, it will be automatically generated.
 ///
 /// # Example
@@ -377,12 +474,12 @@ pub fn dynamic_choice(input: TokenStream) -> TokenStream {
                 #key_tokens,
                 #expr
                 .into_iter()
-                .map(|(t, l)| (t as u8, ifengine::view::Line::from(l)))
+                .map(|(t, l)| (t as u16, ifengine::view::Line::from(l), None))
                 .collect()
             ));
 
-            __ifengine_page_state.remove_mask_last(#key_tokens).map(|x|
-                unsafe { std::mem::transmute::<u8, _>(x) }
+            __ifengine_page_state.take_mask_last(#key_tokens).map(|x|
+                unsafe { std::mem::transmute::<u8, _>(x as u8) }
             )
         }
     };
@@ -456,7 +553,7 @@ pub fn dchoice(input: TokenStream) -> TokenStream {
         quote! {}
     } else {
         quote! {
-            if let Some(__ifengine_id) = __ifengine_page_state.remove_mask_last(#key_tokens) {
+            if let Some(__ifengine_id) = __ifengine_page_state.take_mask_last(#key_tokens) {
                 match __ifengine_id as usize {
                     #(#arms)*
                     #catch_all
@@ -472,13 +569,11 @@ pub fn dchoice(input: TokenStream) -> TokenStream {
                 #expr
                 .iter()
                 .enumerate()
-                .map(|(i, l)| (i as u8, ifengine::view::Line::from(l.clone())))
+                .map(|(i, l)| (i as u16, ifengine::view::Line::from(l.clone()), None))
                 .collect()
             ));
 
-            if let Some(__ifengine_id) = __ifengine_page_state.remove_mask_last(#key_tokens) {
-                #match_block
-            }
+            #match_block
         }
     };
 
@@ -562,7 +657,7 @@ pub fn mparagraph(input: TokenStream) -> TokenStream {
             )
         );
 
-        __ifengine_page_state.get_mask::<64>(#key)[..count].to_vec()
+        __ifengine_page_state.get_mask_vec(#key, count)
     }};
 
     expanded.into()
@@ -792,6 +887,59 @@ pub fn img(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Display a text field, returning the submitted string once there is one.
+///
+/// # Syntax
+/// ```ignore
+/// if let Some(name) = input!("Your name") {
+///     p!(format!("Welcome, {name}."));
+/// }
+///
+/// // samples are submitted by the simulator, one branch each
+/// let answer = input!(("riddle"), "Answer", ["a map", "a shadow"]);
+/// ```
+///
+/// # Arguments
+/// - [`MaybeKey`]
+/// - `placeholder`: Hint text displayed in the empty field.
+/// - `samples`: (Optional) Values tried by the simulator. Defaults to the placeholder.
+#[proc_macro]
+pub fn input(input: TokenStream) -> TokenStream {
+    let KeyExprs { maybe_key, exprs } = syn::parse_macro_input!(input as KeyExprs);
+    let key = maybe_key.into_tokens();
+
+    let (placeholder, samples) = match exprs.as_slice() {
+        [placeholder] => (placeholder, None),
+        [placeholder, samples] => (placeholder, Some(samples)),
+        _ => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "input! macro expects a placeholder and optional samples",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let samples = samples.map(|samples| quote! { .with_samples(#samples) });
+
+    let expanded = quote! {{
+        let __ifengine_key = #key;
+        match __ifengine_page_state.get_text(__ifengine_key) {
+            Some(value) => Some(value),
+            None => {
+                __ifengine_page_state.push(ifengine::view::Object::Input(
+                    __ifengine_key,
+                    ifengine::view::Input::new(#placeholder)#samples,
+                ));
+                None
+            }
+        }
+    }};
+
+    expanded.into()
+}
+
 /// Markdown heading.
 ///
 /// # Example
@@ -877,11 +1025,11 @@ struct AltsInput {
 
 impl Parse for AltsInput {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let maybe_key = input.parse()?;
+
         let content;
         syn::bracketed!(content in input);
 
-        let maybe_key = input.parse()?;
-
         let mut list = Vec::new();
         while !content.is_empty() {
             list.push(content.parse()?);
@@ -936,12 +1084,12 @@ impl Parse for AltsInput {
 /// ## Syntax
 ///
 /// ```ignore
-/// alts!(key?, variant?, [expr, expr, ...])
+/// alts!(key?, [expr, expr, ...], variant?)
 /// ```
 ///
 /// - `key` (optional): Explicit state key
-/// - `variant` (optional): `cycle`, `stop`, or `shuffle`
 /// - `expr`: Any value convertible into a `Span`
+/// - `variant` (optional): `Cycle`, `Stop`, or `Shuffle`
 ///
 /// ## Examples
 ///
@@ -960,19 +1108,19 @@ impl Parse for AltsInput {
 /// ```ignore
 /// alts!(
 ///     (5),
-///     shuffle,
 ///     [
 ///         "Attack",
 ///         "Defend",
 ///         "Flee",
-///     ]
+///     ],
+///     Shuffle
 /// )
 /// ```
 ///
 /// ## Notes
 ///
 /// - State is updated via [`ifengine::Action::Inc`] or [`ifengine::Action::Set`].
-/// - Random selection uses the page state's RNG.
+/// - Random selection is seeded from the game seed, the page, and the key, so it is reproducible.
 /// - The macro expands to an expression producing a `Span`.
 /// - Shuffle and Cycle are hidden during simulation.
 #[proc_macro]
@@ -1017,12 +1165,12 @@ pub fn alts(input: TokenStream) -> TokenStream {
                         (prev as usize) >> 1
                     } else {
                         // regenerate, excluding previous index
-                        let new_idx = __ifengine_page_state.rand(alts.len(), &[(prev as usize) >> 1]);
+                        let new_idx = __ifengine_page_state.rand(#key, alts.len(), &[(prev as usize) >> 1]);
                         __ifengine_page_state.insert(#key, (new_idx as u64) << 1);
                         new_idx
                     }
                 } else {
-                    let new_idx = __ifengine_page_state.rand(alts.len(), &[]);
+                    let new_idx = __ifengine_page_state.rand(#key, alts.len(), &[]);
                     __ifengine_page_state.insert(#key, (new_idx as u64) << 1);
                     new_idx
                 } ;
@@ -1196,32 +1344,194 @@ pub fn fresh(input: TokenStream) -> TokenStream {
     expanded.into()
 }
 
+// -------------- TIMERS -------------------------
+
+struct TimedChoiceInput {
+    maybe_key: MaybeKey,
+    timeout: Expr,
+    default: Expr,
+    arms: Vec<LineArm>,
+}
+
+impl Parse for TimedChoiceInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let maybe_key = input.parse()?;
+        let timeout = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let default = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let arms = ChoiceInput::parse_arms(input)?;
+
+        Ok(TimedChoiceInput {
+            maybe_key,
+            timeout,
+            default,
+            arms,
+        })
+    }
+}
+
+/// A [`choice`] which resolves to the `default` index once `timeout` seconds have passed since it was shown.
+///
+/// The countdown runs on the game clock, which is advanced by [`ifengine::Game::tick`], and restarts each time the page is entered.
+/// The simulator treats the timeout as one more branch.
+///
+/// # Arguments
+/// - [`MaybeKey`]
+/// - `timeout`: Seconds until the choice resolves by itself.
+/// - `default`: The index it resolves to.
+/// - The arms, as in [`choice`].
+///
+/// # Example
+/// ```rust,ignore
+/// timed_choice! {
+///     5.0, 1,
+///     "Jump" => "You clear the gap.",
+///     "Hesitate" => "The bridge gives way beneath you.",
+/// };
+/// ```
+#[proc_macro]
+pub fn timed_choice(input: TokenStream) -> TokenStream {
+    let TimedChoiceInput {
+        maybe_key,
+        timeout,
+        default,
+        arms,
+    } = syn::parse_macro_input!(input as TimedChoiceInput);
+
+    let resolved = choice_tokens(quote!(__ifengine_timed_key), &arms);
+    let key = maybe_key.into_tokens();
+
+    let expanded = quote! {{
+        let __ifengine_timed_key = #key;
+        let __ifengine_deadline = __ifengine_page_state.deadline(
+            __ifengine_timed_key,
+            // negative or NaN timeouts are due at once, and those too large to represent never are
+            std::time::Duration::try_from_secs_f64(((#timeout) as f64).max(0.0))
+                .unwrap_or(std::time::Duration::MAX),
+        );
+        let __ifengine_resolved = #resolved;
+        if !__ifengine_resolved {
+            __ifengine_page_state.push(ifengine::view::Object::Timer(
+                ifengine::view::Timer::new(__ifengine_deadline).with_action(
+                    ifengine::Action::Timeout(
+                        (__ifengine_page_state.id(), __ifengine_timed_key),
+                        #default,
+                    ),
+                ),
+            ));
+        }
+        __ifengine_resolved
+    }};
+
+    expanded.into()
+}
+
+/// Returns whether `secs` seconds have passed on the game clock since this element was reached on the current visit of the page.
+///
+/// Until then, a [`ifengine::view::Timer`] is added to the view, so that the frontend refreshes the page when it runs out.
+///
+/// # Example
+/// ```rust,ignore
+/// p!("The lights go out.");
+/// if after!(3.0) {
+///     p!("Something moves in the dark.");
+/// }
+/// ```
+#[proc_macro]
+pub fn after(input: TokenStream) -> TokenStream {
+    let KeyExpr { maybe_key, expr } = syn::parse_macro_input!(input as KeyExpr);
+    let key = maybe_key.into_tokens();
+
+    let expanded = quote! {{
+        let __ifengine_deadline = __ifengine_page_state.deadline(
+            #key,
+            std::time::Duration::try_from_secs_f64(((#expr) as f64).max(0.0))
+                .unwrap_or(std::time::Duration::MAX),
+        );
+        if __ifengine_page_state.clock() >= __ifengine_deadline {
+            true
+        } else {
+            __ifengine_page_state.push(ifengine::view::Object::Timer(
+                ifengine::view::Timer::new(__ifengine_deadline),
+            ));
+            false
+        }
+    }};
+
+    expanded.into()
+}
+
+// -------------- THREADS -------------------------
+
+/// Weave the views of other pages into this one, at the current position.
+///
+/// Once the page has finished, each thread is called until it produces a view, and its objects are inserted where `weave!` was called.
+/// Choices and inputs keep their state under the thread which produced them, so each thread behaves as if it were displayed by itself.
+/// Threads share the visit of the page they are woven into, so their timers and once tags only restart when that page is entered anew.
+/// A thread which responds through [`LINK`] is replaced by the linked page. Otherwise threads can only add to the page: if a thread responds with anything other than a view (i.e. through [`BACK`] or [`END`]), or a page ends up woven into itself, the page responds with an error instead.
+///
+/// # Example
+/// ```ignore
+/// #[ifview]
+/// pub fn camp(s: &mut State) {
+///     p!("The fire crackles.");
+///     weave!(talk_to_elias, check_supplies);
+/// }
+/// ```
+#[proc_macro]
+pub fn weave(input: TokenStream) -> TokenStream {
+    let threads = parse_macro_input!(input with Punctuated<syn::Path, Token![,]>::parse_terminated);
+
+    let threads = threads.iter().map(|thread| {
+        quote! {
+            __ifengine_page_state.weave(ifengine::core::PageHandle::new(
+                stringify!(#thread).into(),
+                #thread,
+            ));
+        }
+    });
+
+    quote! {{ #(#threads)* }}.into()
+}
+
+/// Read the value returned by the last tunnel entered from this page, see [`EXIT`].
+///
+/// Evaluates to `Option<T>`, which is `None` if the tunnel exited without a value, or with a value of a different type.
+/// The value is kept while the page is shown, and cleared once another page is viewed. It is not saved with [`ifengine::core::GameSave`].
+///
+/// # Example
+/// ```ignore
+/// if let Some(won) = tunnel_result!(bool) {
+///     p!(if won { "You won the round." } else { "You lost the round." });
+/// }
+/// ```
+#[proc_macro]
+pub fn tunnel_result(input: TokenStream) -> TokenStream {
+    let ty = parse_macro_input!(input as syn::Type);
+
+    quote! {
+        __ifengine_page_state.tunnel_result::<#ty>()
+    }
+    .into()
+}
+
 // -------------- SPANS -------------------------
 
 /// Create a link [`Span`] that navigates backward.
 ///
 /// - `$e`: Display text.
 /// - `$n`: Optional number of steps to go back (defaults to 1).
-///
-/// # Additional
-/// This option will be hidden during simulation if no number is specified
 #[proc_macro]
 pub fn back(input: TokenStream) -> TokenStream {
     let ExprAndOptional { expr, n } = parse_macro_input!(input as ExprAndOptional);
 
-    let expanded = if let Some(n_expr) = n {
-        quote! {
-            ifengine::view::Span::from(#expr)
-            .as_link()
-            .with_action(ifengine::Action::Back(#n_expr))
-        }
-    } else {
-        quote! {
-            ifengine::view::Span::from(#expr)
-            .as_link()
-            .with_action(ifengine::Action::Back(1))
-            .no_sim()
-        }
+    let n_expr = n.map(|n| quote! { #n }).unwrap_or(quote! { 1 });
+
+    let expanded = quote! {
+        ifengine::view::Span::from(#expr)
+        .as_link()
+        .with_action(ifengine::Action::Back(#n_expr))
     };
 
     TokenStream::from(expanded)
@@ -1240,6 +1550,44 @@ pub fn r#YIELD(_input: TokenStream) -> TokenStream {
 }
 
 // ------------ KEY OPERATIONS -------------------
+/// Offset the keys of all elements within `body` by `index`.
+///
+/// Elements inside a loop or closure expand at a single call site, so they would otherwise share one key.
+///
+/// # Example
+/// ```ignore
+/// let choices: Vec<_> = names
+///     .iter()
+///     .enumerate()
+///     .map(|(i, name)| key_scope!(i, click!(("name"), *name)))
+///     .collect();
+/// dchoice!(choices);
+///
+/// for i in 0..names.len() {
+///     if key_scope!(i, read_key!("name")).is_some() { /* .. */ }
+/// }
+/// ```
+#[proc_macro]
+pub fn key_scope(input: TokenStream) -> TokenStream {
+    let ExprAndOptional {
+        expr: index,
+        n: body,
+    } = parse_macro_input!(input as ExprAndOptional);
+
+    let Some(body) = body else {
+        return Error::new_spanned(index, "expected key_scope!(index, body)")
+            .to_compile_error()
+            .into();
+    };
+
+    let expanded = quote! {{
+        let __ifengine_scope = __ifengine_page_state.push_scope((#index) as u64);
+        #body
+    }};
+
+    expanded.into()
+}
+
 /// Read the value of a key of the internal [`PageState`]
 ///
 /// Elements push to the view in the order they are called.
@@ -1247,6 +1595,8 @@ pub fn r#YIELD(_input: TokenStream) -> TokenStream {
 ///
 /// Beware that the implementation details of the internal page state that these keys index is internal and should not be relied on!
 ///
+/// Choice keys hold a [`Selection`](ifengine::core::game_state::Selection) rather than a value, so they read as `None`: use [`read_selection`] or [`read_key_mask`] instead.
+///
 /// # Example
 /// ```rust
 /// let value = read_key!(my_key);
@@ -1254,9 +1604,10 @@ pub fn r#YIELD(_input: TokenStream) -> TokenStream {
 #[proc_macro]
 pub fn read_key(input: TokenStream) -> TokenStream {
     let expr = syn::parse_macro_input!(input as syn::Expr);
+    let key = key_tokens(&expr);
 
     let expanded = quote! {
-        __ifengine_page_state.get(#expr)
+        __ifengine_page_state.get(#key)
     };
 
     expanded.into()
@@ -1264,6 +1615,8 @@ pub fn read_key(input: TokenStream) -> TokenStream {
 
 /// Read a key as a bitmask. See [`read_key`].
 ///
+/// Choice elements store their state as a [`Selection`](ifengine::core::game_state::Selection), which this reads; `n` may exceed 64.
+///
 /// # Example
 /// ```rust
 /// let mask = read_key_mask!(my_key); // [bool; 64]
@@ -1274,6 +1627,7 @@ pub fn read_key_mask(input: TokenStream) -> TokenStream {
     let ExprAndOptional { expr: key, n } = syn::parse_macro_input!(input as ExprAndOptional);
 
     let n = n.unwrap_or_else(|| syn::parse_quote!(64));
+    let key = key_tokens(&key);
 
     quote! {
         __ifengine_page_state.get_mask::<#n>(#key)
@@ -1281,6 +1635,27 @@ pub fn read_key_mask(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Read the [`Selection`](ifengine::core::game_state::Selection) of a choice key, or an empty one. See [`read_key`].
+///
+/// This records the order and number of picks, in addition to the mask of [`read_key_mask`].
+///
+/// # Example
+/// ```rust,ignore
+/// mchoice!(("party"), "Oracle", "Walker", "Interpreter");
+/// let second = read_selection!("party").nth(1); // Option<u16>
+/// let times = read_selection!("party").count(0);
+/// ```
+#[proc_macro]
+pub fn read_selection(input: TokenStream) -> TokenStream {
+    let expr = syn::parse_macro_input!(input as syn::Expr);
+    let key = key_tokens(&expr);
+
+    quote! {
+        __ifengine_page_state.selection(#key).unwrap_or_default()
+    }
+    .into()
+}
+
 /// Set a key to a value. See [`read_key`].
 ///
 /// # Example
@@ -1321,9 +1696,10 @@ pub fn set_key_mask(input: TokenStream) -> TokenStream {
             .to_compile_error()
             .into();
     };
+    let key = key_tokens(key);
     let bits: Vec<&Expr> = iter.collect();
 
-    let mut mask = 0u64;
+    let mut indices = Vec::new();
     for expr in &bits {
         if let Expr::Lit(syn::ExprLit {
             lit: syn::Lit::Int(i),
@@ -1331,7 +1707,7 @@ pub fn set_key_mask(input: TokenStream) -> TokenStream {
         }) = expr
         {
             match i.base10_parse::<usize>() {
-                Ok(bit) => mask |= 1u64 << bit,
+                Ok(bit) => indices.push(bit),
                 Err(_) => {
                     return syn::Error::new_spanned(i, "failed to parse bit position")
                         .to_compile_error()
@@ -1346,10 +1722,7 @@ pub fn set_key_mask(input: TokenStream) -> TokenStream {
     }
 
     let expanded = quote! {
-        {
-            let old = __ifengine_page_state.get(#key).unwrap_or(0u64);
-            __ifengine_page_state.insert(#key, old | #mask);
-        }
+        __ifengine_page_state.set_mask(#key, &[#(#indices),*])
     };
 
     expanded.into()
@@ -1378,9 +1751,10 @@ pub fn unset_key_mask(input: TokenStream) -> TokenStream {
             .to_compile_error()
             .into();
     };
+    let key = key_tokens(key);
     let bits: Vec<&Expr> = iter.collect();
 
-    let mut mask = 0u64;
+    let mut indices = Vec::new();
     for expr in &bits {
         if let Expr::Lit(syn::ExprLit {
             lit: syn::Lit::Int(i),
@@ -1388,7 +1762,7 @@ pub fn unset_key_mask(input: TokenStream) -> TokenStream {
         }) = expr
         {
             match i.base10_parse::<usize>() {
-                Ok(bit) => mask |= 1u64 << bit,
+                Ok(bit) => indices.push(bit),
                 Err(_) => {
                     return syn::Error::new_spanned(i, "failed to parse bit position")
                         .to_compile_error()
@@ -1403,10 +1777,7 @@ pub fn unset_key_mask(input: TokenStream) -> TokenStream {
     }
 
     let expanded = quote! {
-        {
-            let old = __ifengine_page_state.get(#key).unwrap_or(0u64);
-            __ifengine_page_state.insert(#key, old & !#mask);
-        }
+        __ifengine_page_state.unset_mask(#key, &[#(#indices),*])
     };
 
     expanded.into()
@@ -1422,9 +1793,11 @@ pub fn unset_key_mask(input: TokenStream) -> TokenStream {
 pub fn inc_key(input: TokenStream) -> TokenStream {
     let expr = syn::parse_macro_input!(input as syn::Expr);
 
+    let key = key_tokens(&expr);
+
     let expanded = quote! {
         {
-            let k = #expr;
+            let k = #key;
             let v = __ifengine_page_state.get(k).unwrap_or(0);
             __ifengine_page_state.insert(k, v.wrapping_add(1));
         }
@@ -1433,7 +1806,7 @@ pub fn inc_key(input: TokenStream) -> TokenStream {
     expanded.into()
 }
 
-/// Reset (remove) a key from state. See [`read_key`].
+/// Reset (remove) a key from state, including the selection of a choice and the text of an [`input`]. See [`read_key`].
 ///
 /// # Example
 /// ```rust
@@ -1443,8 +1816,10 @@ pub fn inc_key(input: TokenStream) -> TokenStream {
 pub fn reset_key(input: TokenStream) -> TokenStream {
     let expr = syn::parse_macro_input!(input as syn::Expr);
 
+    let key = key_tokens(&expr);
+
     let expanded = quote! {
-        __ifengine_page_state.remove(#expr)
+        __ifengine_page_state.remove(#key)
     };
 
     expanded.into()
@@ -1452,17 +1827,20 @@ pub fn reset_key(input: TokenStream) -> TokenStream {
 
 // ------------ TAGS ------------------
 
-// note: this doesn't work
-/// [Tags](crate::core::GameTags) the current page.
+/// [Tags](ifengine::core::GameTags) the current page, see [`ifengine::view::TagKind`].
+///
+/// - By default, the tag applies to the current view only.
+/// - Pass `Sticky` to persist the tag in the game tags between pages.
+/// - Pass `Once` to persist the tag, but only add it to the views of the visit it first fires on.
 ///
-/// Pass `Sticky` to persist the tag between pages.
+/// Returns false if a `Once` tag fired on an earlier visit, or a `Sticky` tag was already present.
 ///
 /// # Examples
 ///
 /// ```rust
-/// tag!(my_value);          // non-sticky tag
-/// tag!(my_value, Sticky);  // sticky tag
-/// tag!(my_value, Once);    // apply only once
+/// tag!("rain");          // view tag
+/// tag!("wet", Sticky);   // sticky tag
+/// tag!("thunder", Once); // apply only once
 /// ```
 #[proc_macro]
 pub fn tag(input: TokenStream) -> TokenStream {
@@ -1492,21 +1870,20 @@ pub fn tag(input: TokenStream) -> TokenStream {
 
     let TagInput { expr, mode } = parse_macro_input!(input as TagInput);
 
-    let sticky = match mode {
+    let kind = match mode {
         Some(id) => match id.to_string().as_str() {
-            "Sticky" => true,
-            "Once" => false,
+            "Sticky" | "Once" => quote!(ifengine::view::TagKind::#id),
             _ => {
                 return syn::Error::new_spanned(&id, "Expected `Sticky` or `Once`")
                     .to_compile_error()
                     .into();
             }
         },
-        None => false,
+        None => quote!(ifengine::view::TagKind::View),
     };
 
     let expanded = quote! {
-        __ifengine_page_state.tag(#expr, #sticky)
+        __ifengine_page_state.tag(#expr, #kind)
     };
 
     expanded.into()
@@ -1534,6 +1911,56 @@ pub fn in_sim(_: TokenStream) -> TokenStream {
     expanded.into()
 }
 
+// ------------ VARS ------------------
+
+/// Read a game-wide [variable](ifengine::core::Vars), as an `Option<&Var>`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let gold = var!("gold").and_then(Var::as_int).unwrap_or(0);
+/// ```
+#[proc_macro]
+pub fn var(input: TokenStream) -> TokenStream {
+    let name = syn::parse_macro_input!(input as syn::Expr);
+
+    let expanded = quote! {
+        __ifengine_game.vars.get(#name)
+    };
+
+    expanded.into()
+}
+
+/// Set a game-wide [variable](ifengine::core::Vars) to any value convertible into a `Var`.
+///
+/// To change a variable on click instead, attach [`ifengine::Action::SetVar`], [`ifengine::Action::IncVar`] or [`ifengine::Action::ToggleVar`] to a span.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// set_var!("met_elias", true);
+/// set_var!("gold", 12);
+/// ```
+#[proc_macro]
+pub fn set_var(input: TokenStream) -> TokenStream {
+    let ExprAndOptional {
+        expr: name,
+        n: value,
+    } = parse_macro_input!(input as ExprAndOptional);
+
+    let Some(value) = value else {
+        return Error::new_spanned(name, "Expected a name and a value")
+            .to_compile_error()
+            .into();
+    };
+
+    let expanded = quote! {
+        __ifengine_game.vars.set(#name, #value)
+    };
+
+    expanded.into()
+}
+
 // ------------ UTILS ------------------
 
 /// Debug display the current [`PageState`]
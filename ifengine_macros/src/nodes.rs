@@ -1,38 +1,75 @@
+use std::collections::HashMap;
+
+use const_fnv1a_hash::fnv1a_hash_str_64;
+use proc_macro2::{Delimiter, Group, Ident, Spacing, Span, TokenStream as TokenStream2, TokenTree};
 use quote::quote;
 use syn::{
-    Expr, Result, Token,
-    parse::{Parse, ParseStream},
+    Expr, Lit, Result, Token,
+    parse::{Parse, ParseStream, Parser},
     punctuated::Punctuated,
 };
 
-fn unique_id() -> u64 {
-    let span = proc_macro::Span::call_site();
-    let start = span.start();
-    ((start.line() as u64) << 32) | (start.column() as u64)
-}
-
-/// Optional u64 key specified in the first position, surrounded in brackets.
+/// Optional key specified in the first position, surrounded in brackets.
 /// The internal data describing an element is stored under this key in the page state and can be retrieved for full fine-grained control.
+///
+/// The key can be a u64 expression, or a string label which is hashed at compile time.
+/// If no key is given, [`ifengine::ifview`] derives one from the element's source: its name and arguments.
+/// This stays the same when other elements are added, removed or reordered, but editing the element itself changes it, so give a label to elements whose state should survive such edits.
+/// Only elements with the same source are told apart by position, i.e. the number of such elements before it on the page.
+/// Elements which the page can't see, i.e. those produced by a `macro_rules!` defined elsewhere, fall back to a hash of their arguments.
+///
+/// Inside a `key_scope!`, all keys are offset by the scope's index, so elements generated in a loop don't collide.
 /// # Syntax
 /// ```rust
 /// let span_count = read_key!(6); // Can be called before
 /// let span = count!((6), |val| "span");
+///
+/// let picked = read_key!("companion");
+/// choice!(("companion"), "Elias", "Mira");
+///
+/// for i in 0..3 {
+///     key_scope!(i, p!(count!(|n| format!("{n}"))));
+/// }
 /// ```
 pub enum MaybeKey {
-    Auto,
+    Auto(u64),
     Key(Expr),
 }
 
 impl MaybeKey {
     pub fn into_tokens(self) -> proc_macro2::TokenStream {
         match self {
-            MaybeKey::Key(key_expr) => quote!(#key_expr),
-            MaybeKey::Auto => {
-                let uid = unique_id();
-                quote!(#uid)
-            }
+            MaybeKey::Key(key_expr) => key_tokens(&key_expr),
+            MaybeKey::Auto(uid) => quote!(__ifengine_page_state.scoped(#uid)),
         }
     }
+
+    /// The key, if it is known at compile time.
+    pub fn value(&self) -> Option<u64> {
+        match self {
+            MaybeKey::Auto(uid) => Some(*uid),
+            MaybeKey::Key(Expr::Lit(lit)) => match &lit.lit {
+                Lit::Str(s) => Some(fnv1a_hash_str_64(&s.value())),
+                Lit::Int(i) => i.base10_parse().ok(),
+                _ => None,
+            },
+            MaybeKey::Key(_) => None,
+        }
+    }
+}
+
+/// String literals are hashed into a key, other expressions are passed through.
+/// The key is then offset by any enclosing `key_scope!`.
+pub fn key_tokens(expr: &Expr) -> proc_macro2::TokenStream {
+    if let Expr::Lit(syn::ExprLit {
+        lit: Lit::Str(s), ..
+    }) = expr
+    {
+        let key = fnv1a_hash_str_64(&s.value());
+        quote!(__ifengine_page_state.scoped(#key))
+    } else {
+        quote!(__ifengine_page_state.scoped(#expr))
+    }
 }
 
 impl Parse for MaybeKey {
@@ -44,11 +81,305 @@ impl Parse for MaybeKey {
             let _ = input.parse::<Token![,]>();
             Ok(MaybeKey::Key(key_expr))
         } else {
-            Ok(MaybeKey::Auto)
+            let source = canonical(input.cursor().token_stream());
+            Ok(MaybeKey::Auto(fnv1a_hash_str_64(&source)))
         }
     }
 }
 
+/// Elements which take a [`MaybeKey`].
+const KEYED_ELEMENTS: &[&str] = &[
+    "choice",
+    "mchoice",
+    "dynamic_choice",
+    "dchoice",
+    "dparagraph",
+    "mparagraph",
+    "alts",
+    "count",
+    "click",
+    "input",
+    "after",
+    "timed_choice",
+];
+
+/// The name of the keyed element invoked by the tokens before a group, i.e. `choice` in `choice!(..)`.
+fn keyed_element(preceding: &[TokenTree]) -> Option<&Ident> {
+    match preceding {
+        [.., TokenTree::Ident(name), TokenTree::Punct(bang)]
+            if bang.as_char() == '!' && KEYED_ELEMENTS.contains(&name.to_string().as_str()) =>
+        {
+            Some(name)
+        }
+        _ => None,
+    }
+}
+
+fn parse_key(stream: TokenStream2) -> Option<MaybeKey> {
+    let parser = |input: ParseStream| {
+        let key: MaybeKey = input.parse()?;
+        input.parse::<TokenStream2>()?;
+        Ok(key)
+    };
+    parser.parse2(stream).ok()
+}
+
+/// Give each keyed element in a page body which has no key one derived from its source, see [`MaybeKey`].
+pub fn assign_keys(body: TokenStream2) -> TokenStream2 {
+    assign_keys_in(body, &mut HashMap::new())
+}
+
+fn assign_keys_in(tokens: TokenStream2, counts: &mut HashMap<u64, u64>) -> TokenStream2 {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+    let mut out = Vec::with_capacity(tokens.len());
+    for (i, token) in tokens.iter().enumerate() {
+        let TokenTree::Group(group) = token else {
+            out.push(token.clone());
+            continue;
+        };
+
+        let mut stream = group.stream();
+        if let Some(name) = keyed_element(&tokens[..i])
+            && let Some(MaybeKey::Auto(_)) = parse_key(stream.clone())
+        {
+            let source = format!("{name}!({})", canonical(stream.clone()));
+            let hash = fnv1a_hash_str_64(&source);
+            let n = counts.entry(hash).or_default();
+            let key = match *n {
+                0 => hash,
+                n => fnv1a_hash_str_64(&format!("{source}#{n}")),
+            };
+            *n += 1;
+            stream = quote!((#key), #stream);
+        }
+
+        let mut rewritten = Group::new(group.delimiter(), assign_keys_in(stream, counts));
+        rewritten.set_span(group.span());
+        out.push(TokenTree::Group(rewritten));
+    }
+
+    out.into_iter().collect()
+}
+
+/// The source of some tokens, written out the same way on every toolchain, unlike [`TokenStream2::to_string`], whose spacing may change.
+///
+/// Each token is followed by a space, except punctuation joined to the next token, i.e. `=>`, and groups are written between their delimiters.
+fn canonical(tokens: TokenStream2) -> String {
+    let mut out = String::new();
+    write_canonical(tokens, &mut out);
+    out
+}
+
+fn write_canonical(tokens: TokenStream2, out: &mut String) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => out.push_str(&ident.to_string()),
+            TokenTree::Literal(lit) => out.push_str(&lit.to_string()),
+            TokenTree::Punct(punct) => {
+                out.push(punct.as_char());
+                if punct.spacing() == Spacing::Joint {
+                    continue;
+                }
+            }
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                write_canonical(group.stream(), out);
+                out.push_str(close);
+            }
+        }
+        out.push(' ');
+    }
+}
+
+/// Report elements in a page body whose keys are known at compile time to be the same.
+///
+/// The branches of an `if`/`else` chain, and the arms of a `match`, are checked separately, since only one of them runs.
+pub fn check_duplicate_keys(body: TokenStream2) -> Result<()> {
+    let mut seen = HashMap::new();
+    let mut errors: Option<syn::Error> = None;
+    let tokens: Vec<TokenTree> = body.into_iter().collect();
+    collect_keys(&tokens, &mut seen, &mut errors);
+    errors.map_or(Ok(()), Err)
+}
+
+fn collect_keys(
+    tokens: &[TokenTree],
+    seen: &mut HashMap<u64, Span>,
+    errors: &mut Option<syn::Error>,
+) {
+    let mut i = 0;
+    while i < tokens.len() {
+        if is_ident(&tokens[i], "if") {
+            i = collect_branches(tokens, i, seen, errors);
+            continue;
+        }
+        if is_ident(&tokens[i], "match") {
+            i = collect_arms(tokens, i, seen, errors);
+            continue;
+        }
+
+        if let TokenTree::Group(group) = &tokens[i] {
+            if let Some(name) = keyed_element(&tokens[..i])
+                && let Some(key) = parse_key(group.stream())
+                && let Some(key) = key.value()
+                && let Some(first) = seen.insert(key, name.span())
+            {
+                let mut e = syn::Error::new(
+                    name.span(),
+                    format!(
+                        "`{name}!` has the same key as another element on this page: give one of them a label, i.e. `{name}!((\"label\"), ...)`"
+                    ),
+                );
+                e.combine(syn::Error::new(first, "first used here"));
+                match errors {
+                    Some(errors) => errors.combine(e),
+                    None => *errors = Some(e),
+                }
+            }
+
+            let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+            collect_keys(&inner, seen, errors);
+        }
+
+        i += 1;
+    }
+}
+
+/// Check an `if`/`else` chain starting at `tokens[start]`, returning the index after it.
+///
+/// Conditions are checked against the surrounding keys, and each branch against the surrounding keys and its own.
+fn collect_branches(
+    tokens: &[TokenTree],
+    start: usize,
+    seen: &mut HashMap<u64, Span>,
+    errors: &mut Option<syn::Error>,
+) -> usize {
+    let mut branches = HashMap::new();
+    let mut i = start;
+
+    loop {
+        // `if <condition> { .. }`
+        let Some(body) = (i + 1..tokens.len()).find(|&j| is_brace(&tokens[j])) else {
+            collect_keys(&tokens[i + 1..], seen, errors);
+            return tokens.len();
+        };
+        collect_keys(&tokens[i + 1..body], seen, errors);
+        collect_branch(&group_tokens(&tokens[body]), seen, &mut branches, errors);
+        i = body + 1;
+
+        if !tokens.get(i).is_some_and(|t| is_ident(t, "else")) {
+            break;
+        }
+        match tokens.get(i + 1) {
+            Some(t) if is_ident(t, "if") => i += 1,
+            Some(t) if is_brace(t) => {
+                collect_branch(&group_tokens(t), seen, &mut branches, errors);
+                i += 2;
+                break;
+            }
+            _ => break,
+        }
+    }
+
+    seen.extend(branches);
+    i
+}
+
+/// Check a `match` starting at `tokens[start]`, returning the index after it.
+///
+/// The scrutinee and the patterns are checked against the surrounding keys, and each arm against the surrounding keys and its own.
+fn collect_arms(
+    tokens: &[TokenTree],
+    start: usize,
+    seen: &mut HashMap<u64, Span>,
+    errors: &mut Option<syn::Error>,
+) -> usize {
+    // `match <scrutinee> { .. }`
+    let Some(body) = (start + 1..tokens.len()).find(|&j| is_brace(&tokens[j])) else {
+        collect_keys(&tokens[start + 1..], seen, errors);
+        return tokens.len();
+    };
+    collect_keys(&tokens[start + 1..body], seen, errors);
+
+    let arms = group_tokens(&tokens[body]);
+    let mut branches = HashMap::new();
+    let mut i = 0;
+    while i < arms.len() {
+        // `<pattern> => <expression>,`
+        let Some(arrow) = (i..arms.len()).find(|&j| is_arrow(&arms[j..])) else {
+            collect_keys(&arms[i..], seen, errors);
+            break;
+        };
+        collect_keys(&arms[i..arrow], seen, errors);
+
+        let expr = arrow + 2;
+        let end = if arms.get(expr).is_some_and(is_brace) {
+            expr + 1
+        } else {
+            (expr..arms.len())
+                .find(|&j| is_punct(&arms[j], ','))
+                .unwrap_or(arms.len())
+        };
+        collect_branch(&arms[expr..end], seen, &mut branches, errors);
+
+        i = end;
+        if arms.get(i).is_some_and(|t| is_punct(t, ',')) {
+            i += 1;
+        }
+    }
+
+    seen.extend(branches);
+    body + 1
+}
+
+fn collect_branch(
+    tokens: &[TokenTree],
+    seen: &HashMap<u64, Span>,
+    branches: &mut HashMap<u64, Span>,
+    errors: &mut Option<syn::Error>,
+) {
+    let mut branch = seen.clone();
+    collect_keys(tokens, &mut branch, errors);
+    branches.extend(branch);
+}
+
+/// The tokens inside a group, or none for any other token.
+fn group_tokens(token: &TokenTree) -> Vec<TokenTree> {
+    match token {
+        TokenTree::Group(group) => group.stream().into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_ident(token: &TokenTree, name: &str) -> bool {
+    matches!(token, TokenTree::Ident(ident) if ident == name)
+}
+
+fn is_punct(token: &TokenTree, ch: char) -> bool {
+    matches!(token, TokenTree::Punct(punct) if punct.as_char() == ch)
+}
+
+/// Whether the tokens start with `=>`.
+fn is_arrow(tokens: &[TokenTree]) -> bool {
+    match tokens {
+        [TokenTree::Punct(eq), TokenTree::Punct(gt), ..] => {
+            eq.as_char() == '=' && eq.spacing() == Spacing::Joint && gt.as_char() == '>'
+        }
+        _ => false,
+    }
+}
+
+fn is_brace(token: &TokenTree) -> bool {
+    matches!(token, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace)
+}
+
 pub struct KeyExpr {
     pub maybe_key: MaybeKey,
     pub expr: Expr,
@@ -3,7 +3,7 @@ use crate::{chap1d::*, saltwrack::State};
 use ifengine::elements::{ChoiceVariant::*, back, choice, click, dp, h, mchoice, p, ps, ts};
 use ifengine::{
     BACK, END, LINK,
-    elements::{dchoice, read_key},
+    elements::{dchoice, key_scope, read_key},
     ifview, l, link, s, tun,
     utils::MaskExt,
 };
@@ -43,14 +43,14 @@ pub fn p3(state: &mut State) {
     let choices: Vec<_> = names
         .chunks_exact(2)
         .enumerate()
-        .map(|(i, x)| [click!((i as u64), x[0]), s!(".  ", x[1])])
+        .map(|(i, x)| key_scope!(i, [click!(("name"), x[0]), s!(".  ", x[1])]))
         .collect();
 
     dchoice!(choices);
 
     for i in 0..3 {
-        if read_key!(i).is_some() {
-            state.myname = names[i as usize * 2].to_string();
+        if key_scope!(i, read_key!("name")).is_some() {
+            state.myname = names[i * 2].to_string();
             LINK!(p4)
         }
     }
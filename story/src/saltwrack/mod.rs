@@ -1,5 +1,7 @@
 use std::{borrow::Cow, collections::HashSet};
 
+use ifengine::core::Achievement;
+
 pub mod chap1;
 pub(crate) mod chap1d;
 
@@ -8,6 +10,24 @@ pub fn new() -> Game {
     ifengine::Game!(chap1::p1)
 }
 
+pub type Achievements = ifengine::core::Achievements<State>;
+pub fn achievements() -> Achievements {
+    Achievements::new()
+        .with(
+            Achievement::new("crew", "Expedition leader", |s: &State, _| {
+                !s.c1.name.is_empty() && !s.c2.name.is_empty()
+            })
+            .with_description("Select an oracle and a saltwalker."),
+        )
+        .with(
+            Achievement::new("interpreter", "A second opinion", |s: &State, _| {
+                s.part1.seen.contains("interpreter_2")
+            })
+            .with_description("Hear out the second interpreter.")
+            .with_hidden(true),
+        )
+}
+
 #[derive(Debug, Default, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State {
@@ -4,3 +4,8 @@ pub type Game = ifengine::Game<()>;
 pub fn new() -> Game {
     ifengine::Game!(chap1::rainy_day)
 }
+
+pub type Achievements = ifengine::core::Achievements<()>;
+pub fn achievements() -> Achievements {
+    Default::default()
+}